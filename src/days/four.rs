@@ -1,17 +1,48 @@
+use std::fmt;
+use std::fmt::Formatter;
 use std::fs;
-use std::ops::Range;
-use advent2022::DailyChallenge;
+use advent2022::{Arguments, DailyChallenge};
 
-pub struct Four {}
+pub struct Four {
+    coverage: bool,
+    camp_overlaps: bool,
+    camp_pairs_path: Option<String>,
+    visualise: Option<String>,
+    diagram_width: Result<u32, String>,
+    overlap_metrics: bool,
+    overlap_metrics_path: Option<String>,
+    reassign: bool,
+    reassign_camp: bool,
+}
+
+impl Four {
+    pub fn from(arguments: &Arguments) -> Four {
+        Four {
+            coverage: arguments.flag("--coverage"),
+            camp_overlaps: arguments.flag("--camp-overlaps"),
+            camp_pairs_path: arguments.value("--camp-pairs").map(String::from),
+            visualise: arguments.value("--visualise").map(String::from),
+            diagram_width: arguments.number("--diagram-width").map(|width| width.unwrap_or(80)),
+            overlap_metrics: arguments.flag("--overlap-metrics"),
+            overlap_metrics_path: arguments.value("--overlap-metrics-csv").map(String::from),
+            reassign: arguments.flag("--reassign"),
+            reassign_camp: arguments.flag("--reassign-camp"),
+        }
+    }
+}
 
 impl DailyChallenge for Four {
     fn run(&self) {
         println!("Day Four");
-        let section_assignments: Vec<Assignments> = fs::read_to_string("contents/day_four.txt")
-            .expect("Should have been able to read file")
-            .lines()
-            .map(|x| Assignments::from(x).unwrap())
-            .collect();
+        let contents = fs::read_to_string("contents/day_four.txt")
+            .expect("Should have been able to read file");
+        let section_assignments = match parse_assignments(&contents) {
+            Ok(section_assignments) => section_assignments,
+            Err(error) => {
+                println!("Unable to read the section assignments: {}", error);
+                return;
+            }
+        };
 
         let part_one: usize = section_assignments
             .iter()
@@ -24,59 +55,670 @@ impl DailyChallenge for Four {
             .filter(|&assignment| assignment.overlaps())
             .count();
         println!("The result of part two is {:?}", part_two);
+
+        if let Some(lines) = &self.visualise {
+            match (SectionRange::from(lines), &self.diagram_width) {
+                (_, Err(error)) => println!("Unable to visualise lines {}: {}", lines, error),
+                (Ok(lines), _) if lines.start == 0 => println!("Unable to visualise lines {}: line numbers start at 1", lines),
+                (Ok(lines), _) if lines.end as usize > section_assignments.len() => {
+                    println!("Unable to visualise lines {}: the file only has {} lines", lines, section_assignments.len())
+                }
+                (Ok(lines), Ok(width)) => {
+                    let shown = &section_assignments[lines.start as usize - 1..lines.end as usize];
+                    print!("{}", Diagram::from(shown, lines.start as usize, *width));
+                }
+                (Err(error), _) => println!("Unable to visualise lines {}: {}", lines, error),
+            }
+        }
+        if self.coverage {
+            print!("{}", Coverage::from(&section_assignments));
+        }
+        if self.camp_overlaps || self.camp_pairs_path.is_some() {
+            let camp_overlaps = CampOverlaps::from(&section_assignments);
+            print!("{}", camp_overlaps);
+            if let Some(camp_pairs_path) = &self.camp_pairs_path {
+                match fs::write(camp_pairs_path, format!("{}{}", CampOverlaps::CSV_HEADER, camp_overlaps.csv())) {
+                    Ok(()) => println!("Wrote the overlapping pairs to {}", camp_pairs_path),
+                    Err(error) => println!("Unable to write the overlapping pairs to {}: {}", camp_pairs_path, error),
+                }
+            }
+        }
+        if self.overlap_metrics || self.overlap_metrics_path.is_some() {
+            let metrics = OverlapMetrics::from(&section_assignments);
+            if self.overlap_metrics {
+                print!("{}", metrics);
+            }
+            if let Some(overlap_metrics_path) = &self.overlap_metrics_path {
+                match fs::write(overlap_metrics_path, format!("{}{}", OverlapMetrics::CSV_HEADER, metrics.csv())) {
+                    Ok(()) => println!("Wrote the overlap metrics to {}", overlap_metrics_path),
+                    Err(error) => println!("Unable to write the overlap metrics to {}: {}", overlap_metrics_path, error),
+                }
+            }
+        }
+        if self.reassign {
+            let mut changed = 0;
+            let mut cost = 0;
+            let mut released = 0;
+            let mut exact = true;
+            for (index, assignment) in section_assignments.iter().enumerate() {
+                let reassignment = Reassignment::from(&assignment.elves(index + 1));
+                if reassignment.cost > 0 {
+                    let original: Vec<String> = assignment.ranges.iter().map(SectionRange::to_string).collect();
+                    let approximate = if reassignment.exact { "" } else { ", approximate" };
+                    println!("line {}: {} -> {} (moves {} sections{})",
+                             index + 1, original.join(","), reassignment.describe(), reassignment.cost, approximate);
+                    changed += 1;
+                    exact &= reassignment.exact;
+                    cost += reassignment.cost;
+                    released += reassignment.released();
+                }
+            }
+            println!("Reassigning {} lines removes every overlap, moving endpoints by {} sections and releasing {} elves",
+                     changed, cost, released);
+            if !exact {
+                println!("Groups of more than {} overlapping elves were reassigned approximately and may move more than needed",
+                         EXACT_REASSIGNMENT);
+            }
+        }
+        if self.reassign_camp {
+            let elves: Vec<Elf> = section_assignments
+                .iter()
+                .enumerate()
+                .flat_map(|(index, assignment)| assignment.elves(index + 1))
+                .collect();
+            let reassignment = Reassignment::from(&elves);
+            for (elf, range) in &reassignment.elves {
+                if *range != Some(elf.range) {
+                    println!("{} -> {}", elf, range.map_or(String::from("none"), |range| range.to_string()));
+                }
+            }
+            println!("Reassigning the whole camp moves endpoints by {} sections and releases {} of {} elves",
+                     reassignment.cost, reassignment.released(), elves.len());
+            if !reassignment.exact {
+                println!("Groups of more than {} overlapping elves were reassigned approximately and may move more than needed",
+                         EXACT_REASSIGNMENT);
+            }
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct Assignments { first: Range<u32>, second: Range<u32> }
+fn parse_assignments(contents: &str) -> Result<Vec<Assignments>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| Assignments::from(line).map_err(|reason| format!("line {}: {}", index + 1, reason)))
+        .collect()
+}
 
-impl Assignments {
-    fn from(code: &str) -> Result<Assignments, &'static str> {
-        fn get_range(aaa: &str) -> Range<u32> {
-            let mut parts = aaa.split("-").map(|x| x.parse::<u32>().unwrap());
-            let start = parts.next().unwrap();
-            let end = parts.next().unwrap();
-            std::ops::Range { start: start, end: end }
-        }
-
-        let section_assignments: Vec<Range<u32>> = code
-            .split(",")
-            .map(|part| {
-                get_range(part)
-            }).collect();
-
-        if let Some(first_section) = section_assignments.first() {
-            if let Some(second_section) = section_assignments.get(1) {
-                return Ok( Assignments {
-                    first: first_section.clone(),
-                    second: second_section.clone()
-                })
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct SectionRange { start: u32, end: u32 }
+
+impl SectionRange {
+    fn from(code: &str) -> Result<SectionRange, &'static str> {
+        let (start, end) = code.split_once('-').ok_or("a section range must look like start-end")?;
+        let start = start.parse::<u32>().map_err(|_| "the start of a section range must be a number")?;
+        let end = end.parse::<u32>().map_err(|_| "the end of a section range must be a number")?;
+        SectionRange::new(start, end).ok_or("a section range cannot end before it starts")
+    }
+
+    fn new(start: u32, end: u32) -> Option<SectionRange> {
+        if end < start {
+            return None;
+        }
+        Some(SectionRange { start, end })
+    }
+
+    fn contains(&self, section: u32) -> bool {
+        self.start <= section && section <= self.end
+    }
+
+    fn contains_range(&self, other: &SectionRange) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    fn overlaps(&self, other: &SectionRange) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    fn intersection(&self, other: &SectionRange) -> Option<SectionRange> {
+        SectionRange::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    fn len(&self) -> u64 {
+        self.end as u64 - self.start as u64 + 1
+    }
+
+    fn is_empty(&self) -> bool {
+        self.end < self.start
+    }
+}
+
+impl fmt::Display for SectionRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+struct SectionSet { ranges: Vec<SectionRange> }
+
+impl SectionSet {
+    fn from(ranges: &[SectionRange]) -> SectionSet {
+        let mut sorted: Vec<SectionRange> = ranges.iter().filter(|range| !range.is_empty()).copied().collect();
+        sorted.sort_by_key(|range| range.start);
+        let mut merged: Vec<SectionRange> = Vec::new();
+        for range in sorted {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end.saturating_add(1) => last.end = last.end.max(range.end),
+                _ => merged.push(range),
             }
         }
+        SectionSet { ranges: merged }
+    }
 
-        Err("Error")
+    fn union(&self, other: &SectionSet) -> SectionSet {
+        SectionSet::from(&[self.ranges.as_slice(), other.ranges.as_slice()].concat())
     }
 
-    fn fully_contains(&self) -> bool {
-        fn inner_contain(first: &Range<u32>, second: &Range<u32>) -> bool {
-            if first.start <= second.start {
-                if first.end >= second.end {
-                    return true;
+    fn intersection(&self, other: &SectionSet) -> SectionSet {
+        let mut ranges = Vec::new();
+        let (mut mine, mut theirs) = (0, 0);
+        while mine < self.ranges.len() && theirs < other.ranges.len() {
+            let (first, second) = (self.ranges[mine], other.ranges[theirs]);
+            ranges.extend(first.intersection(&second));
+            if first.end < second.end {
+                mine += 1;
+            } else {
+                theirs += 1;
+            }
+        }
+        SectionSet { ranges }
+    }
+
+    fn difference(&self, other: &SectionSet) -> SectionSet {
+        let mut ranges = Vec::new();
+        for range in &self.ranges {
+            let mut start = range.start;
+            let mut remaining = true;
+            for removed in other.ranges.iter().filter(|removed| removed.overlaps(range)) {
+                if removed.start > start {
+                    ranges.push(SectionRange { start, end: removed.start - 1 });
                 }
+                if removed.end >= range.end {
+                    remaining = false;
+                    break;
+                }
+                start = removed.end + 1;
+            }
+            if remaining {
+                ranges.push(SectionRange { start, end: range.end });
             }
-            false
         }
-        inner_contain(&self.first, &self.second) || inner_contain(&self.second, &self.first)
+        SectionSet { ranges }
     }
 
-    fn overlaps(&self) -> bool {
-        fn inner_overlap(first: &Range<u32>, second: &Range<u32>) -> bool {
-            if first.start <= second.start && second.start <= first.end {
-                return true;
+    fn len(&self) -> u64 {
+        self.ranges.iter().map(SectionRange::len).sum()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+impl fmt::Display for SectionSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "none");
+        }
+        let ranges: Vec<String> = self.ranges.iter().map(SectionRange::to_string).collect();
+        write!(f, "{}", ranges.join(", "))
+    }
+}
+
+struct Coverage { covered: SectionSet, uncovered: SectionSet, repeated: SectionSet, redundant: u64 }
+
+impl Coverage {
+    fn from(section_assignments: &[Assignments]) -> Coverage {
+        let mut covered = SectionSet::default();
+        let mut repeated = SectionSet::default();
+        let mut assigned = 0;
+        for range in section_assignments.iter().flat_map(|assignment| assignment.ranges.iter().copied()) {
+            let range = SectionSet::from(&[range]);
+            repeated = repeated.union(&covered.intersection(&range));
+            covered = covered.union(&range);
+            assigned += range.len();
+        }
+        // Sections are numbered from 1 as in the puzzle, so any before the first cleaned one are uncovered too.
+        let span = match (covered.ranges.first(), covered.ranges.last()) {
+            (Some(first), Some(last)) => SectionSet::from(&[SectionRange { start: first.start.min(1), end: last.end }]),
+            _ => SectionSet::default(),
+        };
+        Coverage { uncovered: span.difference(&covered), redundant: assigned - covered.len(), covered, repeated }
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Sections cleaned: {} ({} sections)", self.covered, self.covered.len())?;
+        writeln!(f, "Sections no elf cleans: {} ({} sections)", self.uncovered, self.uncovered.len())?;
+        writeln!(f, "Sections cleaned by more than one elf: {} ({} sections)", self.repeated, self.repeated.len())?;
+        writeln!(f, "Redundant work across the camp: {} sections", self.redundant)
+    }
+}
+
+struct Diagram<'a> { assignments: &'a [Assignments], first_line: usize, start: u32, end: u32, scale: u64 }
+
+impl Diagram<'_> {
+    fn from(assignments: &[Assignments], first_line: usize, width: u32) -> Diagram<'_> {
+        let width = width.max(1) as u64;
+        let ranges = assignments.iter().flat_map(|assignment| assignment.ranges.iter());
+        let end = ranges.clone().map(|range| range.end).max().unwrap_or(1);
+        // Sections are numbered from 1 as in the puzzle (or 0 if a range starts there), unless that would
+        // need scaling when skipping the leading empty sections would not.
+        let lowest = ranges.map(|range| range.start).min().unwrap_or(1);
+        let start = if ((end - lowest.min(1)) as u64) < width { lowest.min(1) } else { lowest };
+        let scale = ((end - start) as u64 + 1).div_ceil(width);
+        Diagram { assignments, first_line, start, end, scale }
+    }
+
+    fn blocks(&self) -> impl Iterator<Item = SectionRange> + '_ {
+        (self.start as u64..=self.end as u64)
+            .step_by(self.scale as usize)
+            .map(|start| SectionRange { start: start as u32, end: (start + self.scale - 1).min(self.end as u64) as u32 })
+    }
+
+    fn row(&self, range: &SectionRange) -> String {
+        self.blocks()
+            .map(|block| {
+                if self.scale == 1 && range.contains(block.start) {
+                    char::from_digit(block.start % 10, 10).unwrap()
+                } else if self.scale > 1 && range.overlaps(&block) {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    }
+
+    fn overlap(assignment: &Assignments) -> SectionSet {
+        let shared: Vec<SectionRange> = assignment.pairs().flat_map(|(first, second)| first.intersection(second)).collect();
+        SectionSet::from(&shared)
+    }
+}
+
+impl fmt::Display for Diagram<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Sections {}-{}, {} per column", self.start, self.end, self.scale)?;
+        for (index, assignment) in self.assignments.iter().enumerate() {
+            writeln!(f, "line {}", self.first_line + index)?;
+            for range in &assignment.ranges {
+                writeln!(f, "{}  {}", self.row(range), range)?;
+            }
+            let overlap = Diagram::overlap(assignment);
+            if !overlap.is_empty() {
+                let markers: String = self.blocks()
+                    .map(|block| if overlap.ranges.iter().any(|range| range.overlaps(&block)) { '^' } else { ' ' })
+                    .collect();
+                writeln!(f, "{}  overlap {}", markers, overlap)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct Elf { line: usize, position: usize, range: SectionRange }
+
+impl fmt::Display for Elf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} elf {} ({})", self.line, self.position, self.range)
+    }
+}
+
+struct CampOverlaps { elves: usize, pairs: Vec<(Elf, Elf)> }
+
+impl CampOverlaps {
+    const CSV_HEADER: &'static str = "first_line,first_elf,first_sections,second_line,second_elf,second_sections,relation\n";
+
+    fn from(section_assignments: &[Assignments]) -> CampOverlaps {
+        let mut elves: Vec<Elf> = section_assignments
+            .iter()
+            .enumerate()
+            .flat_map(|(index, assignment)| assignment.elves(index + 1))
+            .collect();
+        let count = elves.len();
+        elves.sort_by_key(|elf| (elf.range.start, elf.line, elf.position));
+
+        // Sweep the elves in order of their first section, keeping those whose range has not yet ended.
+        let mut active: Vec<Elf> = Vec::new();
+        let mut pairs = Vec::new();
+        for elf in elves {
+            active.retain(|other| other.range.end >= elf.range.start);
+            for &other in &active {
+                if (other.line, other.position) < (elf.line, elf.position) {
+                    pairs.push((other, elf));
+                } else {
+                    pairs.push((elf, other));
+                }
             }
-            false
+            active.push(elf);
+        }
+        pairs.sort_by_key(|(first, second)| (first.line, first.position, second.line, second.position));
+        CampOverlaps { elves: count, pairs }
+    }
+
+    fn contains(first: &Elf, second: &Elf) -> bool {
+        first.range.contains_range(&second.range) || second.range.contains_range(&first.range)
+    }
+
+    fn containing(&self) -> usize {
+        self.pairs.iter().filter(|(first, second)| CampOverlaps::contains(first, second)).count()
+    }
+
+    fn csv(&self) -> String {
+        self.pairs
+            .iter()
+            .map(|(first, second)| {
+                let relation = if CampOverlaps::contains(first, second) { "contains" } else { "overlaps" };
+                format!("{},{},{},{},{},{},{}\n",
+                        first.line, first.position, first.range, second.line, second.position, second.range, relation)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for CampOverlaps {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} elves across the camp form {} overlapping pairs, {} of which fully contain one another",
+                 self.elves, self.pairs.len(), self.containing())
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Redundancy { Neither, Elf(usize), Either }
+
+impl fmt::Display for Redundancy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Redundancy::Neither => write!(f, "neither"),
+            Redundancy::Elf(position) => write!(f, "elf {}", position),
+            Redundancy::Either => write!(f, "either"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct PairMetrics { first: Elf, second: Elf, overlap: u64, union: u64, redundant: Redundancy }
+
+impl PairMetrics {
+    fn from(first: Elf, second: Elf) -> PairMetrics {
+        let overlap = first.range.intersection(&second.range).map_or(0, |shared| shared.len());
+        let redundant = if first.range == second.range {
+            Redundancy::Either
+        } else if second.range.contains_range(&first.range) {
+            Redundancy::Elf(first.position)
+        } else if first.range.contains_range(&second.range) {
+            Redundancy::Elf(second.position)
+        } else {
+            Redundancy::Neither
+        };
+        PairMetrics { first, second, overlap, union: first.range.len() + second.range.len() - overlap, redundant }
+    }
+
+    fn jaccard(&self) -> f64 {
+        self.overlap as f64 / self.union as f64
+    }
+}
+
+struct OverlapMetrics { pairs: Vec<PairMetrics>, wasted: u64 }
+
+impl OverlapMetrics {
+    const CSV_HEADER: &'static str = "rank,line,first_elf,first_sections,second_elf,second_sections,overlap,jaccard,redundant\n";
+    const WORST: usize = 10;
+
+    fn from(section_assignments: &[Assignments]) -> OverlapMetrics {
+        let mut pairs: Vec<PairMetrics> = section_assignments
+            .iter()
+            .enumerate()
+            .flat_map(|(index, assignment)| {
+                let elves = assignment.elves(index + 1);
+                (0..elves.len())
+                    .flat_map(|first| (first + 1..elves.len()).map(move |second| (first, second)))
+                    .map(|(first, second)| PairMetrics::from(elves[first], elves[second]))
+                    .collect::<Vec<PairMetrics>>()
+            })
+            .collect();
+        // Rank by overlap size, then by Jaccard index compared without rounding, then by line.
+        pairs.sort_by(|a, b| {
+            b.overlap.cmp(&a.overlap)
+                .then((b.overlap as u128 * a.union as u128).cmp(&(a.overlap as u128 * b.union as u128)))
+                .then(a.first.line.cmp(&b.first.line))
+        });
+        // A section cleaned by three elves wastes two cleanings, not the three that pairwise overlaps would count.
+        let wasted = section_assignments
+            .iter()
+            .map(|assignment| {
+                let assigned: u64 = assignment.ranges.iter().map(SectionRange::len).sum();
+                assigned - SectionSet::from(&assignment.ranges).len()
+            })
+            .sum();
+        OverlapMetrics { pairs, wasted }
+    }
+
+    fn csv(&self) -> String {
+        self.pairs
+            .iter()
+            .enumerate()
+            .map(|(index, pair)| format!("{},{},{},{},{},{},{},{:.4},{}\n",
+                                         index + 1, pair.first.line, pair.first.position, pair.first.range,
+                                         pair.second.position, pair.second.range, pair.overlap, pair.jaccard(), pair.redundant))
+            .collect()
+    }
+}
+
+impl fmt::Display for OverlapMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let overlapping: Vec<&PairMetrics> = self.pairs.iter().filter(|pair| pair.overlap > 0).collect();
+        let redundant = self.pairs.iter().filter(|pair| pair.redundant != Redundancy::Neither).count();
+        let mean_jaccard = overlapping.iter().map(|pair| pair.jaccard()).sum::<f64>() / overlapping.len().max(1) as f64;
+        writeln!(f, "{} of {} pairs overlap, wasting {} sections in total", overlapping.len(), self.pairs.len(), self.wasted)?;
+        writeln!(f, "{} pairs have a redundant elf, overlapping pairs have a mean Jaccard index of {:.3}", redundant, mean_jaccard)?;
+        writeln!(f, "Worst overlapping pairs")?;
+        for (index, pair) in overlapping.iter().take(OverlapMetrics::WORST).enumerate() {
+            writeln!(f, "{:>2}. line {}: {} and {} share {} sections, Jaccard {:.3}, redundant: {}",
+                     index + 1, pair.first.line, pair.first.range, pair.second.range, pair.overlap, pair.jaccard(), pair.redundant)?;
         }
-        inner_overlap(&self.first, &self.second) || inner_overlap(&self.second, &self.first)
+        Ok(())
+    }
+}
+
+const EXACT_REASSIGNMENT: usize = 7;
+
+#[derive(Debug, PartialEq)]
+struct Reassignment { elves: Vec<(Elf, Option<SectionRange>)>, cost: u64, exact: bool }
+
+impl Reassignment {
+    // Every elf's range lies inside exactly one component, so each component is reassigned on its own.
+    fn from(elves: &[Elf]) -> Reassignment {
+        let ranges: Vec<SectionRange> = elves.iter().map(|elf| elf.range).collect();
+        let mut new_ranges = vec![None; elves.len()];
+        let mut cost = 0;
+        let mut exact = true;
+        for component in SectionSet::from(&ranges).ranges {
+            let members: Vec<usize> = (0..ranges.len()).filter(|&index| component.contains_range(&ranges[index])).collect();
+            let member_ranges: Vec<SectionRange> = members.iter().map(|&index| ranges[index]).collect();
+            let order = if members.len() <= EXACT_REASSIGNMENT {
+                Reassignment::search(&member_ranges, &component)
+            } else {
+                exact = false;
+                Reassignment::heuristic(&member_ranges, &component)
+            };
+            let ordered: Vec<SectionRange> = order.iter().map(|&index| member_ranges[index]).collect();
+            for (&index, tile) in order.iter().zip(Reassignment::tile(&component, &ordered).0) {
+                new_ranges[members[index]] = Some(tile);
+            }
+            cost += Reassignment::order_cost(&member_ranges, &component, &order);
+        }
+        Reassignment { elves: elves.iter().copied().zip(new_ranges).collect(), cost, exact }
+    }
+
+    fn order_cost(ranges: &[SectionRange], component: &SectionRange, order: &[usize]) -> u64 {
+        let ordered: Vec<SectionRange> = order.iter().map(|&index| ranges[index]).collect();
+        // An elf left without sections has moved its end back past its start.
+        let released: u64 = (0..ranges.len())
+            .filter(|index| !order.contains(index))
+            .map(|index| ranges[index].len())
+            .sum();
+        Reassignment::tile(component, &ordered).1 + released
+    }
+
+    // Elves are only released when there are not enough sections to give every elf one.
+    fn search(ranges: &[SectionRange], component: &SectionRange) -> Vec<usize> {
+        let assigned = (ranges.len() as u64).min(component.len()) as usize;
+        let indices: Vec<usize> = (0..ranges.len()).collect();
+        Reassignment::permutations(&indices)
+            .into_iter()
+            .map(|mut order| {
+                order.truncate(assigned);
+                order
+            })
+            .min_by_key(|order| Reassignment::order_cost(ranges, component, order))
+            .unwrap_or_default()
+    }
+
+    fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+        if items.is_empty() {
+            return vec![vec![]];
+        }
+        (0..items.len())
+            .flat_map(|index| {
+                let mut rest = items.to_vec();
+                let item = rest.remove(index);
+                Reassignment::permutations(&rest).into_iter().map(move |mut permutation| {
+                    permutation.insert(0, item);
+                    permutation
+                })
+            })
+            .collect()
+    }
+
+    // Too many elves to try every order, so they keep the order of their starts, releasing the shortest
+    // ranges where there are more elves than sections. The result may move more than the minimum.
+    fn heuristic(ranges: &[SectionRange], component: &SectionRange) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..ranges.len()).collect();
+        if order.len() as u64 > component.len() {
+            order.sort_by_key(|&index| (ranges[index].len(), index));
+            order.drain(..order.len() - component.len() as usize);
+        }
+        order.sort_by_key(|&index| (ranges[index].start, ranges[index].end, index));
+        order
+    }
+
+    // Splits the component into consecutive ranges, one for each elf in order, moving the endpoints as
+    // little as possible. Substituting u = boundary - position turns the strictly increasing boundaries
+    // into a non-decreasing sequence, whose L1 optimum lies on one of the breakpoints of the costs.
+    fn tile(component: &SectionRange, ranges: &[SectionRange]) -> (Vec<SectionRange>, u64) {
+        let (start, end) = (component.start as i64, component.end as i64);
+        let count = ranges.len() as i64;
+        let fixed = (start - ranges[0].start as i64).abs() + (end - ranges[ranges.len() - 1].end as i64).abs();
+        let (low, high) = (start - 1, end - count);
+        let mut candidates = vec![low, high];
+        for position in 1..ranges.len() {
+            let shift = position as i64 + 1;
+            candidates.push(ranges[position - 1].end as i64 + 1 - shift);
+            candidates.push(ranges[position].start as i64 - shift);
+        }
+        let mut candidates: Vec<i64> = candidates.into_iter().map(|candidate| candidate.clamp(low, high)).collect();
+        candidates.sort();
+        candidates.dedup();
+
+        let boundary_cost = |position: usize, candidate: i64| {
+            let boundary = candidate + position as i64 + 1;
+            (boundary - 1 - ranges[position - 1].end as i64).abs() + (boundary - ranges[position].start as i64).abs()
+        };
+        let mut costs: Vec<i64> = vec![0; candidates.len()];
+        let mut choices: Vec<Vec<usize>> = Vec::new();
+        for position in 1..ranges.len() {
+            let mut choice = Vec::with_capacity(candidates.len());
+            let mut best = 0;
+            let mut next = Vec::with_capacity(candidates.len());
+            for (index, &candidate) in candidates.iter().enumerate() {
+                if costs[index] < costs[best] {
+                    best = index;
+                }
+                choice.push(best);
+                next.push(costs[best] + boundary_cost(position, candidate));
+            }
+            costs = next;
+            choices.push(choice);
+        }
+
+        let mut index = (0..candidates.len()).min_by_key(|&index| (costs[index], index)).unwrap();
+        let mut boundaries = vec![end + 1];
+        for (position, choice) in choices.iter().enumerate().rev() {
+            boundaries.push(candidates[index] + position as i64 + 2);
+            index = choice[index];
+        }
+        boundaries.push(start);
+        boundaries.reverse();
+        let tiles = boundaries
+            .windows(2)
+            .map(|pair| SectionRange { start: pair[0] as u32, end: pair[1] as u32 - 1 })
+            .collect();
+        (tiles, (fixed + costs.iter().min().unwrap()) as u64)
+    }
+
+    fn describe(&self) -> String {
+        let ranges: Vec<String> = self.elves
+            .iter()
+            .map(|(_, range)| range.map_or(String::from("none"), |range| range.to_string()))
+            .collect();
+        ranges.join(",")
+    }
+
+    fn released(&self) -> usize {
+        self.elves.iter().filter(|(_, range)| range.is_none()).count()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Assignments { ranges: Vec<SectionRange> }
+
+impl Assignments {
+    fn from(code: &str) -> Result<Assignments, &'static str> {
+        let ranges = code
+            .split(',')
+            .map(SectionRange::from)
+            .collect::<Result<Vec<SectionRange>, &'static str>>()?;
+        if ranges.len() < 2 {
+            return Err("expected at least two section ranges separated by commas");
+        }
+        Ok(Assignments { ranges })
+    }
+
+    fn elves(&self, line: usize) -> Vec<Elf> {
+        self.ranges
+            .iter()
+            .enumerate()
+            .map(|(position, &range)| Elf { line, position: position + 1, range })
+            .collect()
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&SectionRange, &SectionRange)> {
+        self.ranges
+            .iter()
+            .enumerate()
+            .flat_map(|(index, first)| self.ranges[index + 1..].iter().map(move |second| (first, second)))
+    }
+
+    fn fully_contains(&self) -> bool {
+        self.pairs().any(|(first, second)| first.contains_range(second) || second.contains_range(first))
+    }
+
+    fn overlaps(&self) -> bool {
+        self.pairs().any(|(first, second)| first.overlaps(second))
     }
 }
 
@@ -86,7 +728,7 @@ mod tests {
 
     #[test]
     fn create_assignments() {
-        let expected: Assignments = Assignments { first: 2..4, second: 6..8 };
+        let expected: Assignments = Assignments { ranges: vec![SectionRange { start: 2, end: 4 }, SectionRange { start: 6, end: 8 }] };
         let actual = Assignments::from("2-4,6-8");
         assert_eq!(actual, Ok(expected))
     }
@@ -150,4 +792,270 @@ mod tests {
         let actual = Assignments::from("52-52,3-51").unwrap();
         assert_eq!(actual.overlaps(), false);
     }
+
+    #[test]
+    fn reject_reversed_range() {
+        assert_eq!(Assignments::from("8-2,3-4"), Err("a section range cannot end before it starts"));
+    }
+
+    #[test]
+    fn reject_malformed_assignments() {
+        assert_eq!(Assignments::from("2-4"), Err("expected at least two section ranges separated by commas"));
+        assert_eq!(Assignments::from("2-4,6"), Err("a section range must look like start-end"));
+        assert_eq!(Assignments::from("2-x,6-8"), Err("the end of a section range must be a number"));
+        assert_eq!(parse_assignments("2-4,6-8\n8-2,3-4"), Err(String::from("line 2: a section range cannot end before it starts")));
+    }
+
+    #[test]
+    fn section_range_is_inclusive() {
+        let range = SectionRange::from("3-5").unwrap();
+        assert!(range.contains(3));
+        assert!(range.contains(5));
+        assert!(!range.contains(6));
+        assert_eq!(range.len(), 3);
+        assert!(!range.is_empty());
+        assert_eq!(SectionRange::from("6-6").unwrap().len(), 1);
+        assert_eq!(range.to_string(), "3-5");
+    }
+
+    #[test]
+    fn section_range_intersection() {
+        let range = SectionRange::from("3-7").unwrap();
+        assert_eq!(range.intersection(&SectionRange::from("7-9").unwrap()), SectionRange::new(7, 7));
+        assert_eq!(range.intersection(&SectionRange::from("8-9").unwrap()), None);
+        assert!(range.contains_range(&SectionRange::from("3-7").unwrap()));
+        assert!(!range.overlaps(&SectionRange::from("8-9").unwrap()));
+    }
+
+    fn sections(ranges: &[(u32, u32)]) -> SectionSet {
+        let ranges: Vec<SectionRange> = ranges.iter().map(|&(start, end)| SectionRange { start, end }).collect();
+        SectionSet::from(&ranges)
+    }
+
+    #[test]
+    fn section_set_merges_overlapping_and_adjacent_ranges() {
+        assert_eq!(sections(&[(6, 8), (2, 4), (3, 5), (10, 12)]).ranges, sections(&[(2, 8), (10, 12)]).ranges);
+        assert_eq!(sections(&[(2, 4), (6, 8)]).len(), 6);
+        assert_eq!(sections(&[(2, 4), (6, 8)]).to_string(), "2-4, 6-8");
+        assert_eq!(SectionSet::default().to_string(), "none");
+    }
+
+    #[test]
+    fn section_set_algebra() {
+        let first = sections(&[(1, 5), (10, 20)]);
+        let second = sections(&[(4, 12), (18, 30)]);
+        assert_eq!(first.union(&second), sections(&[(1, 30)]));
+        assert_eq!(first.intersection(&second), sections(&[(4, 5), (10, 12), (18, 20)]));
+        assert_eq!(first.difference(&second), sections(&[(1, 3), (13, 17)]));
+        assert_eq!(second.difference(&first), sections(&[(6, 9), (21, 30)]));
+        assert!(first.difference(&sections(&[(0, 40)])).is_empty());
+    }
+
+    #[test]
+    fn camp_coverage() {
+        let section_assignments = parse_assignments("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n12-14,13-13").unwrap();
+        let coverage = Coverage::from(&section_assignments);
+        assert_eq!(coverage.covered, sections(&[(2, 9), (12, 14)]));
+        assert_eq!(coverage.uncovered, sections(&[(1, 1), (10, 11)]));
+        assert_eq!(coverage.repeated, sections(&[(2, 8), (13, 13)]));
+        assert_eq!(coverage.redundant, 46 - 11);
+    }
+
+    #[test]
+    fn coverage_from_section_zero() {
+        let coverage = Coverage::from(&parse_assignments("0-1,3-4").unwrap());
+        assert_eq!(coverage.uncovered, sections(&[(2, 2)]));
+    }
+
+    #[test]
+    fn overlapping_pairs_across_the_camp() {
+        let section_assignments = parse_assignments("2-4,6-8\n2-3,4-5\n5-7,7-9").unwrap();
+        let camp_overlaps = CampOverlaps::from(&section_assignments);
+        let pairs: Vec<String> = camp_overlaps.pairs
+            .iter()
+            .map(|(first, second)| format!("{} & {}", first, second))
+            .collect();
+        assert_eq!(pairs, vec![
+            "line 1 elf 1 (2-4) & line 2 elf 1 (2-3)",
+            "line 1 elf 1 (2-4) & line 2 elf 2 (4-5)",
+            "line 1 elf 2 (6-8) & line 3 elf 1 (5-7)",
+            "line 1 elf 2 (6-8) & line 3 elf 2 (7-9)",
+            "line 2 elf 2 (4-5) & line 3 elf 1 (5-7)",
+            "line 3 elf 1 (5-7) & line 3 elf 2 (7-9)",
+        ]);
+        assert_eq!(camp_overlaps.containing(), 1);
+        assert_eq!(camp_overlaps.to_string(), "6 elves across the camp form 6 overlapping pairs, 1 of which fully contain one another\n");
+        assert!(camp_overlaps.csv().starts_with("1,1,2-4,2,1,2-3,contains\n1,1,2-4,2,2,4-5,overlaps\n"));
+    }
+
+    #[test]
+    fn sweep_matches_pairwise_checks() {
+        let contents = fs::read_to_string("contents/day_four.txt").unwrap();
+        let section_assignments = parse_assignments(&contents).unwrap();
+        let ranges: Vec<SectionRange> = section_assignments
+            .iter()
+            .take(200)
+            .flat_map(|assignment| assignment.ranges.iter().copied())
+            .collect();
+        let expected = (0..ranges.len())
+            .flat_map(|first| (first + 1..ranges.len()).map(move |second| (first, second)))
+            .filter(|&(first, second)| ranges[first].overlaps(&ranges[second]))
+            .count();
+        assert_eq!(CampOverlaps::from(&section_assignments[..200]).pairs.len(), expected);
+    }
+
+    #[test]
+    fn assignments_with_three_elves() {
+        let actual = Assignments::from("2-4,6-8,3-3").unwrap();
+        assert_eq!(actual.ranges.len(), 3);
+        assert!(actual.fully_contains());
+        assert!(actual.overlaps());
+    }
+
+    #[test]
+    fn assignments_with_disjoint_elves() {
+        let actual = Assignments::from("1-2,7-9,3-4,5-6").unwrap();
+        assert!(!actual.fully_contains());
+        assert!(!actual.overlaps());
+        assert!(Assignments::from("1-2,7-9,3-4,4-6").unwrap().overlaps());
+    }
+
+    #[test]
+    fn diagram_of_the_example() {
+        let section_assignments = parse_assignments("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8").unwrap();
+        let diagram = Diagram::from(&section_assignments[..3], 1, 80).to_string();
+        assert_eq!(diagram, "Sections 1-9, 1 per column\n\
+            line 1\n.234.....  2-4\n.....678.  6-8\n\
+            line 2\n.23......  2-3\n...45....  4-5\n\
+            line 3\n....567..  5-7\n......789  7-9\n      ^    overlap 7-7\n");
+    }
+
+    #[test]
+    fn diagram_scales_wide_ranges() {
+        let section_assignments = parse_assignments("1-40,31-60").unwrap();
+        let diagram = Diagram::from(&section_assignments, 5, 10).to_string();
+        assert_eq!(diagram, "Sections 1-60, 6 per column\n\
+            line 5\n#######...  1-40\n.....#####  31-60\n     ^^     overlap 31-40\n");
+    }
+
+    #[test]
+    fn diagram_skips_leading_sections() {
+        let section_assignments = parse_assignments("95-97,96-99").unwrap();
+        let diagram = Diagram::from(&section_assignments, 1, 10).to_string();
+        assert!(diagram.starts_with("Sections 95-99, 1 per column\nline 1\n567..  95-97\n.6789  96-99\n"));
+    }
+
+    #[test]
+    fn diagram_from_section_zero() {
+        let diagram = Diagram::from(&parse_assignments("0-0,0-0").unwrap(), 1, 80).to_string();
+        assert!(diagram.starts_with("Sections 0-0, 1 per column\nline 1\n0  0-0\n0  0-0\n"));
+        let diagram = Diagram::from(&parse_assignments("0-3,2-2").unwrap(), 1, 80).to_string();
+        assert!(diagram.starts_with("Sections 0-3, 1 per column\nline 1\n0123  0-3\n..2.  2-2\n"));
+    }
+
+    #[test]
+    fn diagram_of_the_full_section_range() {
+        let diagram = Diagram::from(&parse_assignments("0-4294967295,1-2").unwrap(), 1, 4).to_string();
+        assert!(diagram.starts_with("Sections 0-4294967295, 1073741824 per column\nline 1\n####  0-4294967295\n#...  1-2\n"));
+    }
+
+    #[test]
+    fn pair_metrics() {
+        let elves = Assignments::from("2-8,3-7").unwrap().elves(4);
+        let metrics = PairMetrics::from(elves[0], elves[1]);
+        assert_eq!(metrics.overlap, 5);
+        assert_eq!(metrics.jaccard(), 5.0 / 7.0);
+        assert_eq!(metrics.redundant, Redundancy::Elf(2));
+
+        let elves = Assignments::from("5-7,7-9,5-7").unwrap().elves(1);
+        assert_eq!(PairMetrics::from(elves[0], elves[1]).redundant, Redundancy::Neither);
+        assert_eq!(PairMetrics::from(elves[0], elves[2]).redundant, Redundancy::Either);
+        assert_eq!(PairMetrics::from(elves[1], elves[2]).overlap, 1);
+    }
+
+    #[test]
+    fn overlap_metrics_ranked() {
+        let section_assignments = parse_assignments("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8").unwrap();
+        let metrics = OverlapMetrics::from(&section_assignments);
+        let ranked: Vec<usize> = metrics.pairs.iter().map(|pair| pair.first.line).collect();
+        assert_eq!(ranked, vec![4, 6, 5, 3, 1, 2]);
+        assert_eq!(metrics.wasted, 10);
+        assert_eq!(OverlapMetrics::from(&parse_assignments("1-5,1-5,1-5").unwrap()).wasted, 10);
+        let report = metrics.to_string();
+        assert!(report.starts_with("4 of 6 pairs overlap, wasting 10 sections in total\n2 pairs have a redundant elf"));
+        assert!(report.contains(" 1. line 4: 2-8 and 3-7 share 5 sections, Jaccard 0.714, redundant: elf 2\n"));
+        assert!(metrics.csv().starts_with("1,4,1,2-8,2,3-7,5,0.7143,elf 2\n2,6,1,2-6,2,4-8,3,0.4286,neither\n"));
+    }
+
+    #[test]
+    fn overlap_metrics_of_the_full_section_range() {
+        let metrics = OverlapMetrics::from(&parse_assignments("0-4294967295,0-4294967295").unwrap());
+        assert_eq!(metrics.pairs[0].overlap, 4294967296);
+        assert_eq!(metrics.wasted, 4294967296);
+    }
+
+    fn reassign(code: &str) -> Reassignment {
+        Reassignment::from(&Assignments::from(code).unwrap().elves(1))
+    }
+
+    #[test]
+    fn reassign_the_example_pairs() {
+        assert_eq!((reassign("2-4,6-8").describe(), reassign("2-4,6-8").cost), (String::from("2-4,6-8"), 0));
+        assert_eq!((reassign("5-7,7-9").describe(), reassign("5-7,7-9").cost), (String::from("5-6,7-9"), 1));
+        assert_eq!((reassign("2-8,3-7").describe(), reassign("2-8,3-7").cost), (String::from("2-2,3-8"), 7));
+        assert_eq!((reassign("6-6,4-6").describe(), reassign("6-6,4-6").cost), (String::from("6-6,4-5"), 1));
+        assert_eq!((reassign("2-6,4-8").describe(), reassign("2-6,4-8").cost), (String::from("2-3,4-8"), 3));
+    }
+
+    #[test]
+    fn reassign_releases_surplus_elves() {
+        let reassignment = reassign("3-3,3-3,1-1");
+        assert_eq!(reassignment.describe(), "3-3,none,1-1");
+        assert_eq!((reassignment.cost, reassignment.released()), (1, 1));
+    }
+
+    #[test]
+    fn reassign_each_component_exactly() {
+        let reassignment = reassign("2-6,11-20,15-18,16-17");
+        assert_eq!((reassignment.cost, reassignment.exact), (10, true));
+        let elves: Vec<Elf> = (0..20)
+            .flat_map(|line| Assignments::from(&format!("{0}-{1},{0}-{1}", line * 10 + 1, line * 10 + 4)).unwrap().elves(line as usize + 1))
+            .collect();
+        let reassignment = Reassignment::from(&elves);
+        assert_eq!((reassignment.cost, reassignment.released(), reassignment.exact), (80, 0, true));
+    }
+
+    #[test]
+    fn large_components_are_approximate() {
+        let reassignment = reassign("1-8,1-8,1-8,1-8,1-8,1-8,1-8,1-8,20-21");
+        assert_eq!(reassignment.describe(), "1-1,2-2,3-3,4-4,5-5,6-6,7-7,8-8,20-21");
+        assert!(!reassignment.exact);
+    }
+
+    #[test]
+    fn reassignment_keeps_coverage_without_overlaps() {
+        let contents = fs::read_to_string("contents/day_four.txt").unwrap();
+        let section_assignments = parse_assignments(&contents).unwrap();
+        let elves: Vec<Elf> = section_assignments
+            .iter()
+            .take(20)
+            .enumerate()
+            .flat_map(|(index, assignment)| assignment.elves(index + 1))
+            .collect();
+        for reassignment in [Reassignment::from(&elves), Reassignment::from(&elves[..6])] {
+            let original: Vec<SectionRange> = reassignment.elves.iter().map(|(elf, _)| elf.range).collect();
+            let mut tiles: Vec<SectionRange> = reassignment.elves.iter().flat_map(|(_, range)| *range).collect();
+            assert_eq!(SectionSet::from(&tiles), SectionSet::from(&original));
+            tiles.sort_by_key(|range| range.start);
+            assert!(tiles.windows(2).all(|pair| !pair[0].overlaps(&pair[1])));
+            let moved: u64 = reassignment.elves
+                .iter()
+                .map(|(elf, range)| match range {
+                    Some(range) => (range.start.abs_diff(elf.range.start) + range.end.abs_diff(elf.range.end)) as u64,
+                    None => elf.range.len(),
+                })
+                .sum();
+            assert_eq!(moved, reassignment.cost);
+        }
+    }
 }
@@ -1,56 +1,107 @@
-use std::fs;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader};
 use advent2022::DailyChallenge;
 
-struct Accumulator {
-    total_calories_per_elf: Vec<u32>,
-    current_total: u32
-}
-
 pub struct One {}
 
 impl DailyChallenge for One {
     fn run(&self) {
         println!("Day One");
-        let values = read_file();
-        let mut per_elf_calories = extract_totals(&values);
-        per_elf_calories.sort_by(|a, &b| b.cmp(a));
-        println!("The result of part one is {:?}", per_elf_calories[0]);
-        let top_three:u32 = (&per_elf_calories[0..3]).iter().sum();
-        println!("The result of part two is {:#?}", top_three);
+        let file = File::open("contents/day_one.txt")
+            .expect("Should have been able to read file");
+        let top_three = top_totals(BufReader::new(file), 3)
+            .expect("Should have been able to read file");
+        match top_three.first() {
+            Some(most) => {
+                println!("The result of part one is {:?}", most);
+                let top_three: u64 = top_three.iter().sum();
+                println!("The result of part two is {:#?}", top_three);
+            }
+            None => println!("Unable to find any elves in the calorie list"),
+        }
     }
 }
 
-fn extract_totals(values: &Vec<Option<u32>>) -> Vec<u32> {
-    let initial_accumulator = Accumulator {
-        total_calories_per_elf: vec![],
-        current_total: 0,
-    };
-    let data = values.iter()
-        .fold(initial_accumulator, |accumlator, item| {
-            match item {
-                Some(value) => {
-                    Accumulator {
-                        current_total: accumlator.current_total + value,
-                        ..accumlator
-                    }
-                }
-                None => {
-                    let mut totals = accumlator.total_calories_per_elf;
-                    totals.push(accumlator.current_total);
-                    Accumulator {
-                        current_total: 0,
-                        total_calories_per_elf: totals
-                    }
-                }
+fn top_totals<R: BufRead>(reader: R, count: usize) -> io::Result<Vec<u64>> {
+    fn keep_largest(heap: &mut BinaryHeap<Reverse<u64>>, total: u64, count: usize) {
+        heap.push(Reverse(total));
+        if heap.len() > count {
+            heap.pop();
+        }
+    }
+
+    let mut heap = BinaryHeap::with_capacity(count + 1);
+    let mut current_total = 0;
+    let mut elf_in_progress = false;
+    for line in reader.lines() {
+        match line?.parse::<u64>().ok() {
+            Some(value) => {
+                current_total += value;
+                elf_in_progress = true;
+            }
+            None => {
+                keep_largest(&mut heap, current_total, count);
+                current_total = 0;
+                elf_in_progress = false;
             }
-        });
-    data.total_calories_per_elf
+        }
+    }
+    if elf_in_progress {
+        keep_largest(&mut heap, current_total, count);
+    }
+
+    let mut totals: Vec<u64> = heap.into_iter().map(|Reverse(total)| total).collect();
+    totals.sort_by(|a, b| b.cmp(a));
+    Ok(totals)
 }
 
-fn read_file() -> Vec<Option<u32>> {
-    fs::read_to_string("contents/day_one.txt")
-        .expect("Should have been able to read file")
-        .lines()
-        .map(|x| x.parse::<u32>().ok())
-        .collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+    #[test]
+    fn top_totals_of_example() {
+        assert_eq!(top_totals(EXAMPLE.as_bytes(), 3).unwrap(), vec![24000, 11000, 10000]);
+    }
+
+    #[test]
+    fn top_totals_counts_last_elf_without_trailing_blank_line() {
+        assert_eq!(top_totals("100\n\n200\n300".as_bytes(), 1).unwrap(), vec![500]);
+        assert_eq!(top_totals("100\n\n200\n300\n".as_bytes(), 1).unwrap(), vec![500]);
+    }
+
+    #[test]
+    fn top_totals_with_trailing_blank_line() {
+        let input = format!("{}\n\n", EXAMPLE);
+        assert_eq!(top_totals(input.as_bytes(), 1).unwrap(), vec![24000]);
+    }
+
+    #[test]
+    fn top_totals_with_fewer_elves_than_requested() {
+        assert_eq!(top_totals("100\n200".as_bytes(), 3).unwrap(), vec![300]);
+    }
+
+    #[test]
+    fn top_totals_of_empty_input() {
+        assert_eq!(top_totals("".as_bytes(), 3).unwrap(), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn top_totals_of_puzzle_input() {
+        let file = File::open("contents/day_one.txt").unwrap();
+        let top_three = top_totals(BufReader::new(file), 3).unwrap();
+        assert_eq!(top_three[0], 70374);
+        assert_eq!(top_three.iter().sum::<u64>(), 204610);
+    }
+
+    #[test]
+    fn top_totals_beyond_u32() {
+        let input = "4000000000\n4000000000\n\n1";
+        assert_eq!(top_totals(input.as_bytes(), 3).unwrap(), vec![8000000000, 1]);
+    }
 }
@@ -1,96 +1,660 @@
-use std::fs;
-use advent2022::DailyChallenge;
+use std::{fmt, fs};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Formatter;
+use std::ops::{BitAnd, BitOr};
+use advent2022::{Arguments, DailyChallenge};
+
+pub struct Three {
+    group_size: Result<usize, String>,
+    analyse: bool,
+    repack: bool,
+    discover: bool,
+    statistics: bool,
+    statistics_path: Option<String>,
+}
 
-pub struct Three {}
+impl Three {
+    pub fn from(arguments: &Arguments) -> Three {
+        let group_size = arguments.number("--group-size").map(|size| size.unwrap_or(3));
+        let analyse = arguments.flag("--analyse");
+        let repack = arguments.flag("--repack");
+        let discover = arguments.flag("--discover");
+        let statistics = arguments.flag("--statistics");
+        let statistics_path = arguments.value("--statistics-json").map(String::from);
+        Three { group_size, analyse, repack, discover, statistics, statistics_path }
+    }
+}
+
+impl Three {
+    fn print_partition(rucksacks: &[Rucksack], partition: &[[usize; 3]]) {
+        let mut total = 0;
+        for [first, second, third] in partition {
+            let group = [first, second, third].map(|&index| rucksacks[index].items());
+            let badge = (group[0] & group[1] & group[2]).first().unwrap();
+            total += badge.priority();
+            println!("lines {}, {}, {}: {}", first + 1, second + 1, third + 1, badge);
+        }
+        println!("Found {} groups with badge priorities summing to {}", partition.len(), total);
+    }
+}
 
 impl DailyChallenge for Three {
     fn run(&self) {
         println!("Day Three");
-        let rucksacks: Vec<Rucksack> = fs::read_to_string("contents/day_three.txt")
-            .expect("Should have been able to read file")
-            .lines()
-            // .take(12)
-            .map(|rucksack_code| Rucksack::from(rucksack_code).unwrap())
-            .collect();
+        let group_size = match &self.group_size {
+            Ok(group_size) => *group_size,
+            Err(error) => {
+                println!("Unable to read the options: {}", error);
+                return;
+            }
+        };
+        let contents = fs::read_to_string("contents/day_three.txt")
+            .expect("Should have been able to read file");
+        let rucksacks = match parse_rucksacks(&contents) {
+            Ok(rucksacks) => rucksacks,
+            Err(error) => {
+                println!("Unable to read the rucksacks: {}", error);
+                return;
+            }
+        };
 
         let part_one_data: Vec<u16> = rucksacks.iter()
-            .flat_map(|rucksack| rucksack.priority())
+            .flat_map(Rucksack::first_shared_item)
+            .map(ItemType::priority)
             .collect();
 
         let part_one: u16 = part_one_data.iter().sum();
         println!("The result of part one is {:?}", part_one);
 
-        let mut rucksacks = rucksacks.iter();
+        if self.repack {
+            let mut total_swaps = 0;
+            for (index, rucksack) in rucksacks.iter().enumerate() {
+                match rucksack.repack() {
+                    Some((repacked, swaps)) => {
+                        println!("line {}: {} ({} swaps)", index + 1, repacked, swaps);
+                        total_swaps += swaps;
+                    }
+                    None => println!("line {}: {} cannot be repacked into equal compartments", index + 1, rucksack),
+                }
+            }
+            println!("Repacking needs {} swaps in total", total_swaps);
+        }
+
+        if self.discover {
+            match GroupSearch::discover(&rucksacks, SEARCH_LIMIT) {
+                Discovery::Impossible => println!("The rucksacks cannot be split into groups of three sharing exactly one item type"),
+                Discovery::Unique(partition) => {
+                    Three::print_partition(&rucksacks, &partition);
+                    println!("This is the only way to group the rucksacks");
+                }
+                Discovery::Ambiguous(partition) => {
+                    Three::print_partition(&rucksacks, &partition);
+                    println!("There are other ways to group the rucksacks");
+                }
+                Discovery::Undecided(Some(partition)) => {
+                    Three::print_partition(&rucksacks, &partition);
+                    println!("Gave up looking for other groupings after {} steps", SEARCH_LIMIT);
+                }
+                Discovery::Undecided(None) => println!("Gave up looking for a grouping after {} steps", SEARCH_LIMIT),
+            }
+        }
+
+        let groups = match groups(&rucksacks, group_size) {
+            Ok(groups) => groups,
+            Err(error) => {
+                println!("Unable to group the rucksacks: {}", error);
+                return;
+            }
+        };
+        if self.analyse {
+            print!("{}", Analysis::from(&groups));
+        }
+        if self.statistics || self.statistics_path.is_some() {
+            let statistics = Statistics::from(&rucksacks, &groups);
+            if self.statistics {
+                print!("{}", statistics);
+            }
+            if let Some(statistics_path) = &self.statistics_path {
+                match fs::write(statistics_path, statistics.json()) {
+                    Ok(()) => println!("Wrote the rucksack statistics to {}", statistics_path),
+                    Err(error) => println!("Unable to write the rucksack statistics to {}: {}", statistics_path, error),
+                }
+            }
+        }
         let mut part_two = 0;
-        while let (Some(first), Some(second), Some(third)) = (rucksacks.next(), rucksacks.next(), rucksacks.next()) {
-            let badge_type =  Rucksack::badge_item_type(first, second, third).unwrap();
-            let priority = Rucksack::priority_from_char(badge_type).unwrap();
-            part_two += priority;
+        for (index, group) in groups.iter().enumerate() {
+            match Rucksack::badge_item_type(group) {
+                Some(badge_type) => part_two += badge_type.priority(),
+                None => {
+                    println!("Unable to find a badge: {}", GroupError::NoBadge { first_line: index * group_size + 1 });
+                    return;
+                }
+            }
         }
         println!("The result of part_two is {:?}", part_two);
     }
 }
 
-#[derive(Debug)]
+struct Analysis { rucksacks: Vec<Vec<ItemType>>, groups: Vec<Vec<ItemType>>, group_size: usize }
+
+impl Analysis {
+    fn from(groups: &[&[Rucksack]]) -> Analysis {
+        Analysis {
+            rucksacks: groups
+                .iter()
+                .flat_map(|group| group.iter())
+                .map(|rucksack| rucksack.shared_items().item_types())
+                .collect(),
+            groups: groups
+                .iter()
+                .map(|group| Rucksack::common_items(group).item_types())
+                .collect(),
+            group_size: groups.first().map_or(0, |group| group.len()),
+        }
+    }
+
+    fn describe(items: &[ItemType]) -> String {
+        let names: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+        match items.len() {
+            0 => String::from("none <- expected exactly one"),
+            1 => names.join(", "),
+            _ => format!("{} <- expected exactly one", names.join(", ")),
+        }
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Item types in both compartments")?;
+        for (index, items) in self.rucksacks.iter().enumerate() {
+            writeln!(f, "line {}: {}", index + 1, Analysis::describe(items))?;
+        }
+        writeln!(f, "Item types common to each group of {}", self.group_size)?;
+        for (index, items) in self.groups.iter().enumerate() {
+            let first_line = index * self.group_size + 1;
+            writeln!(f, "lines {}-{}: {}", first_line, first_line + self.group_size - 1, Analysis::describe(items))?;
+        }
+        let odd_rucksacks = self.rucksacks.iter().filter(|items| items.len() != 1).count();
+        let odd_groups = self.groups.iter().filter(|items| items.len() != 1).count();
+        writeln!(f, "{} of {} rucksacks and {} of {} groups do not have exactly one shared item type",
+                 odd_rucksacks, self.rucksacks.len(), odd_groups, self.groups.len())
+    }
+}
+
+struct Statistics {
+    item_types: Vec<(ItemType, usize, usize)>,
+    sizes: Vec<(usize, usize)>,
+    badges: Vec<(ItemType, usize)>,
+}
+
+impl Statistics {
+    fn from(rucksacks: &[Rucksack], groups: &[&[Rucksack]]) -> Statistics {
+        let mut items = [0; 53];
+        let mut shared = [0; 53];
+        let mut sizes = BTreeMap::new();
+        for rucksack in rucksacks {
+            let code = rucksack.to_string();
+            for item in code.chars().flat_map(ItemType::from_char) {
+                items[item.0 as usize] += 1;
+            }
+            for item in rucksack.shared_items().item_types() {
+                shared[item.0 as usize] += 1;
+            }
+            *sizes.entry(code.len()).or_insert(0) += 1;
+        }
+        let mut badges = [0; 53];
+        for badge in groups.iter().flat_map(|group| Rucksack::badge_item_type(group)) {
+            badges[badge.0 as usize] += 1;
+        }
+        Statistics {
+            item_types: ItemType::all()
+                .filter(|item| items[item.0 as usize] > 0)
+                .map(|item| (item, items[item.0 as usize], shared[item.0 as usize]))
+                .collect(),
+            sizes: sizes.into_iter().collect(),
+            badges: ItemType::all()
+                .filter(|item| badges[item.0 as usize] > 0)
+                .map(|item| (item, badges[item.0 as usize]))
+                .collect(),
+        }
+    }
+
+    fn most_shared(&self) -> Vec<(ItemType, usize)> {
+        let mut ranked: Vec<(ItemType, usize)> = self.item_types
+            .iter()
+            .filter(|&&(_, _, shared)| shared > 0)
+            .map(|&(item, _, shared)| (item, shared))
+            .collect();
+        ranked.sort_by_key(|&(item, shared)| (Reverse(shared), item));
+        ranked
+    }
+
+    fn json(&self) -> String {
+        let item_types: Vec<String> = self.item_types
+            .iter()
+            .map(|(item, items, shared)| format!(
+                "{{\"type\":\"{}\",\"priority\":{},\"items\":{},\"shared\":{}}}", item, item.priority(), items, shared
+            ))
+            .collect();
+        let most_shared: Vec<String> = self.most_shared().iter().map(|(item, _)| format!("\"{}\"", item)).collect();
+        let sizes: Vec<String> = self.sizes
+            .iter()
+            .map(|(size, rucksacks)| format!("{{\"size\":{},\"rucksacks\":{}}}", size, rucksacks))
+            .collect();
+        let badges: Vec<String> = self.badges
+            .iter()
+            .map(|(item, groups)| format!(
+                "{{\"type\":\"{}\",\"groups\":{},\"priority_total\":{}}}", item, groups, item.priority() as usize * groups
+            ))
+            .collect();
+        format!(
+            "{{\"item_types\":[{}],\"most_shared\":[{}],\"rucksack_sizes\":[{}],\"badges\":[{}]}}\n",
+            item_types.join(","), most_shared.join(","), sizes.join(","), badges.join(",")
+        )
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<5}{:>9}{:>7}{:>7}", "type", "priority", "items", "shared")?;
+        for (item, items, shared) in &self.item_types {
+            writeln!(f, "{:<5}{:>9}{:>7}{:>7}", item, item.priority(), items, shared)?;
+        }
+        let most_shared: Vec<String> = self.most_shared()
+            .iter()
+            .take(5)
+            .map(|(item, shared)| format!("{} ({})", item, shared))
+            .collect();
+        writeln!(f, "Most often shared: {}", most_shared.join(", "))?;
+        writeln!(f, "{:<5}{:>10}", "size", "rucksacks")?;
+        for (size, rucksacks) in &self.sizes {
+            writeln!(f, "{:<5}{:>10}", size, rucksacks)?;
+        }
+        writeln!(f, "{:<6}{:>7}{:>15}", "badge", "groups", "priority total")?;
+        for (item, groups) in &self.badges {
+            writeln!(f, "{:<6}{:>7}{:>15}", item, groups, item.priority() as usize * groups)?;
+        }
+        Ok(())
+    }
+}
+
+fn groups(rucksacks: &[Rucksack], size: usize) -> Result<Vec<&[Rucksack]>, GroupError> {
+    if size == 0 {
+        return Err(GroupError::EmptyGroups);
+    }
+    let groups: Vec<&[Rucksack]> = rucksacks.chunks(size).collect();
+    match groups.last() {
+        Some(last) if last.len() < size => Err(GroupError::PartialGroup {
+            first_line: (groups.len() - 1) * size + 1,
+            found: last.len(),
+            expected: size
+        }),
+        _ => Ok(groups),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum GroupError {
+    EmptyGroups,
+    PartialGroup { first_line: usize, found: usize, expected: usize },
+    NoBadge { first_line: usize },
+}
+
+impl fmt::Display for GroupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupError::EmptyGroups => write!(f, "the group size must be at least 1"),
+            GroupError::PartialGroup { first_line, found, expected } => {
+                write!(f, "the group starting on line {} has {} rucksacks, expected {}", first_line, found, expected)
+            }
+            GroupError::NoBadge { first_line } => {
+                write!(f, "the group starting on line {} has no item type in common", first_line)
+            }
+        }
+    }
+}
+
+const SEARCH_LIMIT: usize = 100_000;
+
+#[derive(Debug, PartialEq)]
+enum Discovery {
+    Impossible,
+    Unique(Vec<[usize; 3]>),
+    Ambiguous(Vec<[usize; 3]>),
+    Undecided(Option<Vec<[usize; 3]>>),
+}
+
+struct GroupSearch {
+    items: Vec<ItemSet>,
+    assigned: Vec<bool>,
+    current: Vec<[usize; 3]>,
+    found: Vec<Vec<[usize; 3]>>,
+    steps: usize,
+    limit: usize,
+}
+
+impl GroupSearch {
+    fn discover(rucksacks: &[Rucksack], limit: usize) -> Discovery {
+        if !rucksacks.len().is_multiple_of(3) {
+            return Discovery::Impossible;
+        }
+        let mut search = GroupSearch {
+            items: rucksacks.iter().map(|rucksack| rucksack.items()).collect(),
+            assigned: vec![false; rucksacks.len()],
+            current: Vec::new(),
+            found: Vec::new(),
+            steps: 0,
+            limit,
+        };
+        search.search();
+        let abandoned = search.steps > search.limit;
+        let mut found = search.found;
+        match found.len() {
+            0 if !abandoned => Discovery::Impossible,
+            1 if !abandoned => Discovery::Unique(found.remove(0)),
+            0 | 1 => Discovery::Undecided(found.pop()),
+            _ => Discovery::Ambiguous(found.remove(0)),
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.found.len() > 1 || self.steps > self.limit
+    }
+
+    fn search(&mut self) {
+        self.steps += 1;
+        if self.finished() {
+            return;
+        }
+        let Some(first) = self.assigned.iter().position(|assigned| !assigned) else {
+            self.found.push(self.current.clone());
+            return;
+        };
+        if !self.partners_remain() {
+            return;
+        }
+        self.assigned[first] = true;
+        for second in first + 1..self.items.len() {
+            let pair = self.items[first] & self.items[second];
+            if self.assigned[second] || pair.0 == 0 {
+                continue;
+            }
+            self.assigned[second] = true;
+            for third in second + 1..self.items.len() {
+                if self.assigned[third] || (pair & self.items[third]).0.count_ones() != 1 {
+                    continue;
+                }
+                self.assigned[third] = true;
+                self.current.push([first, second, third]);
+                self.search();
+                self.current.pop();
+                self.assigned[third] = false;
+                if self.finished() {
+                    break;
+                }
+            }
+            self.assigned[second] = false;
+            if self.finished() {
+                break;
+            }
+        }
+        self.assigned[first] = false;
+    }
+
+    fn partners_remain(&self) -> bool {
+        let unassigned: Vec<usize> = (0..self.items.len()).filter(|&index| !self.assigned[index]).collect();
+        unassigned.iter().all(|&index| {
+            unassigned
+                .iter()
+                .filter(|&&other| other != index && (self.items[index] & self.items[other]).0 != 0)
+                .nth(1)
+                .is_some()
+        })
+    }
+}
+
+fn parse_rucksacks(contents: &str) -> Result<Vec<Rucksack>, PackingListError> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, code)| {
+            Rucksack::from(code).map_err(|cause| PackingListError { line_number: index + 1, cause })
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+struct RucksackError { position: usize, item: Option<char>, reason: &'static str }
+
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.item {
+            Some(item) => write!(f, "column {}: {} (found {:?})", self.position, self.reason, item),
+            None => write!(f, "column {}: {}", self.position, self.reason),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct PackingListError { line_number: usize, cause: RucksackError }
+
+impl fmt::Display for PackingListError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, {}", self.line_number, self.cause)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+struct ItemType(u8);
+
+impl ItemType {
+    fn from_char(code: char) -> Option<ItemType> {
+        u8::try_from(code).ok().and_then(ItemType::from_byte)
+    }
+
+    fn from_byte(code: u8) -> Option<ItemType> {
+        match code {
+            b'a'..=b'z' => Some(ItemType(code - b'a' + 1)),
+            b'A'..=b'Z' => Some(ItemType(code - b'A' + 27)),
+            _ => None
+        }
+    }
+
+    fn from_priority(priority: u16) -> Option<ItemType> {
+        match priority {
+            1..=52 => Some(ItemType(priority as u8)),
+            _ => None
+        }
+    }
+
+    fn all() -> impl Iterator<Item = ItemType> {
+        (1..=52).map(ItemType)
+    }
+
+    fn priority(self) -> u16 {
+        self.0 as u16
+    }
+
+    fn to_char(self) -> char {
+        match self.0 {
+            1..=26 => (b'a' + self.0 - 1) as char,
+            _ => (b'A' + self.0 - 27) as char,
+        }
+    }
+}
+
+impl fmt::Display for ItemType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    fn from(items: &str) -> ItemSet {
+        let bits = items
+            .chars()
+            .flat_map(ItemType::from_char)
+            .fold(0, |bits, item| bits | 1 << item.0);
+        ItemSet(bits)
+    }
+
+    fn first(&self) -> Option<ItemType> {
+        ItemType::from_priority(self.0.trailing_zeros() as u16)
+    }
+
+    fn contains(&self, item: ItemType) -> bool {
+        self.0 & 1 << item.0 != 0
+    }
+
+    fn item_types(self) -> Vec<ItemType> {
+        ItemType::all().filter(|&item| self.contains(item)).collect()
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+}
+
+#[derive(Debug, PartialEq)]
 struct Rucksack {
     first_compartment: String,
     second_compartment: String,
+    first_items: ItemSet,
+    second_items: ItemSet,
 }
 
 impl Rucksack {
-    fn from(code: &str) -> Result<Rucksack, &'static str> {
+    fn from(code: &str) -> Result<Rucksack, RucksackError> {
+        for (index, item) in code.chars().enumerate() {
+            let reason = if !item.is_ascii() {
+                "non-ASCII character"
+            } else if ItemType::from_char(item).is_none() {
+                "item types must be a-z or A-Z"
+            } else {
+                continue;
+            };
+            return Err(RucksackError { position: index + 1, item: Some(item), reason });
+        }
+        if !code.len().is_multiple_of(2) {
+            return Err(RucksackError {
+                position: code.len(),
+                item: None,
+                reason: "odd number of items, the compartments would be unequal"
+            });
+        }
+
         let (first_component, second_compartment) = code.split_at(code.len() / 2);
-        Ok(Rucksack {
-            first_compartment: String::from(first_component),
-            second_compartment: String::from(second_compartment)
-        })
+        Ok(Rucksack::from_compartments(String::from(first_component), String::from(second_compartment)))
     }
 
-    fn badge_item_type(first: &Rucksack, second: &Rucksack, third: &Rucksack) -> Option<char> {
-        if let Some(badge_item_type) = Rucksack::check_compartment(&first.first_compartment, second, third) {
-            return Some(badge_item_type)
-        } else {
-            return Rucksack::check_compartment(&first.second_compartment, second, third);
+    fn from_compartments(first_compartment: String, second_compartment: String) -> Rucksack {
+        Rucksack {
+            first_items: ItemSet::from(&first_compartment),
+            second_items: ItemSet::from(&second_compartment),
+            first_compartment,
+            second_compartment,
         }
     }
 
-    fn check_compartment(compartment: &String, second: &Rucksack, third: &Rucksack) -> Option<char> {
-        for item in compartment.chars() {
-            if second.first_compartment.contains(item) || second.second_compartment.contains(item) {
-                if third.first_compartment.contains(item) || third.second_compartment.contains(item) {
-                    return Some(item);
+    fn repack(&self) -> Option<(Rucksack, usize)> {
+        let size = self.first_compartment.len();
+        let mut first: Vec<ItemType> = self.first_compartment.chars().flat_map(ItemType::from_char).collect();
+        let mut second: Vec<ItemType> = self.second_compartment.chars().flat_map(ItemType::from_char).collect();
+        let counts: Vec<(ItemType, usize, usize)> = self.items()
+            .item_types()
+            .into_iter()
+            .map(|item| {
+                let in_first = first.iter().filter(|&&other| other == item).count();
+                let in_second = second.iter().filter(|&&other| other == item).count();
+                (item, in_first, in_second)
+            })
+            .collect();
+
+        // cost[index][filled] is the fewest items moved when the first `index` item types put `filled` items
+        // in the first compartment, keeping each type wholly in one compartment.
+        let mut cost: Vec<Vec<Option<usize>>> = vec![vec![None; size + 1]; counts.len() + 1];
+        cost[0][0] = Some(0);
+        for (index, &(_, first, second)) in counts.iter().enumerate() {
+            for filled in 0..=size {
+                if let Some(moved) = cost[index][filled] {
+                    let in_second = &mut cost[index + 1][filled];
+                    *in_second = Some(in_second.map_or(moved + first, |best| best.min(moved + first)));
+                    if filled + first + second <= size {
+                        let in_first = &mut cost[index + 1][filled + first + second];
+                        *in_first = Some(in_first.map_or(moved + second, |best| best.min(moved + second)));
+                    }
                 }
             }
         }
-        None
-    }
-
-    fn first_shared_item(&self) -> Option<char> {
-        for item in self.first_compartment.chars() {
-            if self.second_compartment.contains(item) {
-                return Some(item);
+        cost[counts.len()][size]?;
+
+        let mut first_types = ItemSet(0);
+        let mut filled = size;
+        for index in (0..counts.len()).rev() {
+            let (item, first, second) = counts[index];
+            let total = first + second;
+            if filled >= total && cost[index][filled - total].map(|moved| moved + second) == cost[index + 1][filled] {
+                first_types = first_types | ItemSet(1 << item.0);
+                filled -= total;
             }
         }
 
-        None
+        let leaving_first: Vec<usize> = (0..size).filter(|&index| !first_types.contains(first[index])).collect();
+        let leaving_second: Vec<usize> = (0..size).filter(|&index| first_types.contains(second[index])).collect();
+        for (&from_first, &from_second) in leaving_first.iter().zip(&leaving_second) {
+            std::mem::swap(&mut first[from_first], &mut second[from_second]);
+        }
+        let repacked = Rucksack::from_compartments(
+            first.into_iter().map(ItemType::to_char).collect(),
+            second.into_iter().map(ItemType::to_char).collect(),
+        );
+        Some((repacked, leaving_first.len()))
     }
 
-    fn priority_from_char(code: char) -> Option<u16> {
-        match code {
-            'a'..='z' => {
-                let ascii = code as u16;
-                Some(ascii - 96)
-            },
-            'A'..='Z' => {
-                let ascii = code as u16;
-                Some(ascii - 38)
-            }
-            _ => None
-        }
+    fn items(&self) -> ItemSet {
+        self.first_items | self.second_items
+    }
+
+    fn common_items(group: &[Rucksack]) -> ItemSet {
+        group
+            .iter()
+            .map(|rucksack| rucksack.items())
+            .reduce(|common, items| common & items)
+            .unwrap_or(ItemSet(0))
+    }
+
+    fn badge_item_type(group: &[Rucksack]) -> Option<ItemType> {
+        Rucksack::common_items(group).first()
+    }
+
+    fn shared_items(&self) -> ItemSet {
+        self.first_items & self.second_items
     }
 
-    fn priority(&self) -> Option<u16> {
-        self.first_shared_item().map(|shared_item| Rucksack::priority_from_char(shared_item).unwrap())
+    fn first_shared_item(&self) -> Option<ItemType> {
+        self.shared_items().first()
+    }
+}
+
+impl fmt::Display for Rucksack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.first_compartment, self.second_compartment)
     }
 }
 
@@ -98,6 +662,10 @@ impl Rucksack {
 mod tests {
     use super::*;
 
+    fn item_types(codes: &str) -> Vec<ItemType> {
+        codes.chars().flat_map(ItemType::from_char).collect()
+    }
+
     #[test]
     fn create_rucksack() {
         let rucksack = Rucksack::from("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
@@ -105,80 +673,150 @@ mod tests {
         assert_eq!(rucksack.second_compartment, "hcsFMMfFFhFp");
     }
 
+    #[test]
+    fn reject_odd_length() {
+        let expected = RucksackError {
+            position: 5,
+            item: None,
+            reason: "odd number of items, the compartments would be unequal"
+        };
+        assert_eq!(Rucksack::from("abcda").unwrap_err(), expected);
+    }
+
+    #[test]
+    fn reject_non_ascii() {
+        let expected = RucksackError { position: 3, item: Some('é'), reason: "non-ASCII character" };
+        assert_eq!(Rucksack::from("abéd").unwrap_err(), expected);
+    }
+
+    #[test]
+    fn reject_illegal_item_type() {
+        let expected = RucksackError { position: 2, item: Some(';'), reason: "item types must be a-z or A-Z" };
+        assert_eq!(Rucksack::from("a;cd").unwrap_err(), expected);
+    }
+
+    #[test]
+    fn packing_list_error_reports_line() {
+        let error = parse_rucksacks("abcd\nab1d").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 3: item types must be a-z or A-Z (found '1')");
+    }
+
+    #[test]
+    fn display_rucksack() {
+        let rucksack = Rucksack::from("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+        assert_eq!(rucksack.to_string(), "vJrwpWtwJgWrhcsFMMfFFhFp");
+    }
+
     #[test]
     fn find_first_shared_item() {
         let rucksack = Rucksack::from("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
-        assert_eq!(rucksack.first_shared_item(), Some('p'));
+        assert_eq!(rucksack.first_shared_item(), ItemType::from_char('p'));
     }
 
     #[test]
     fn priority_of_an_illegal_char() {
-        assert_eq!(Rucksack::priority_from_char(';'), None);
+        assert_eq!(ItemType::from_char(';').map(ItemType::priority), None);
     }
     #[test]
     fn priority_of_a() {
-        assert_eq!(Rucksack::priority_from_char('a'), Some(1));
+        assert_eq!(ItemType::from_char('a').map(ItemType::priority), Some(1));
     }
 
     #[test]
     fn priority_of_j() {
-        assert_eq!(Rucksack::priority_from_char('j'), Some(10));
+        assert_eq!(ItemType::from_char('j').map(ItemType::priority), Some(10));
     }
 
     #[test]
     fn priority_of_z() {
-        assert_eq!(Rucksack::priority_from_char('z'), Some(26));
+        assert_eq!(ItemType::from_char('z').map(ItemType::priority), Some(26));
     }
 
     #[test]
     fn priority_of_uppercase_a() {
-        assert_eq!(Rucksack::priority_from_char('A'), Some(27));
+        assert_eq!(ItemType::from_char('A').map(ItemType::priority), Some(27));
     }
 
     #[test]
     fn priority_of_uppercase_j() {
-        assert_eq!(Rucksack::priority_from_char('J'), Some(36));
+        assert_eq!(ItemType::from_char('J').map(ItemType::priority), Some(36));
     }
 
     #[test]
     fn priority_of_uppercase_z() {
-        assert_eq!(Rucksack::priority_from_char('Z'), Some(52));
+        assert_eq!(ItemType::from_char('Z').map(ItemType::priority), Some(52));
+    }
+
+    #[test]
+    fn item_type_from_priority_reverses_priority() {
+        for code in ('a'..='z').chain('A'..='Z') {
+            let item = ItemType::from_char(code).unwrap();
+            assert_eq!(ItemType::from_priority(item.priority()), Some(item));
+            assert_eq!(ItemType::from_byte(code as u8), Some(item));
+            assert_eq!(item.to_string(), code.to_string());
+        }
+        assert_eq!(ItemType::from_priority(0), None);
+        assert_eq!(ItemType::from_priority(53), None);
+        assert_eq!(ItemType::from_char('é'), None);
+        assert_eq!(ItemType::from_byte(b'['), None);
+    }
+
+    #[test]
+    fn item_types_in_priority_order() {
+        let items: Vec<ItemType> = ItemType::all().collect();
+        assert_eq!(items.len(), 52);
+        assert!(items.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(items[0].to_char(), 'a');
+        assert_eq!(items[51].to_char(), 'Z');
+        assert!(ItemType::from_char('z') < ItemType::from_char('A'));
+    }
+
+    #[test]
+    fn item_set_intersection() {
+        let shared = ItemSet::from("abcZ") & ItemSet::from("xyZc");
+        assert_eq!(shared, ItemSet::from("cZ"));
+        assert_eq!(shared.first(), ItemType::from_char('c'));
+    }
+
+    #[test]
+    fn empty_item_set_has_no_first_item() {
+        assert_eq!((ItemSet::from("abc") & ItemSet::from("xyz")).first(), None);
     }
 
     #[test]
     fn first_rucksack() {
         let rucksack = Rucksack::from("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
-        assert_eq!(rucksack.priority(), Some(16));
+        assert_eq!(rucksack.first_shared_item().map(ItemType::priority), Some(16));
     }
 
     #[test]
     fn second_rucksack() {
         let rucksack = Rucksack::from("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL").unwrap();
-        assert_eq!(rucksack.priority(), Some(38));
+        assert_eq!(rucksack.first_shared_item().map(ItemType::priority), Some(38));
     }
 
     #[test]
     fn third_rucksack() {
         let rucksack = Rucksack::from("PmmdzqPrVvPwwTWBwg").unwrap();
-        assert_eq!(rucksack.priority(), Some(42));
+        assert_eq!(rucksack.first_shared_item().map(ItemType::priority), Some(42));
     }
 
     #[test]
     fn forth_rucksack() {
         let rucksack = Rucksack::from("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn").unwrap();
-        assert_eq!(rucksack.priority(), Some(22));
+        assert_eq!(rucksack.first_shared_item().map(ItemType::priority), Some(22));
     }
 
     #[test]
     fn fifth_rucksack() {
         let rucksack = Rucksack::from("ttgJtRGJQctTZtZT").unwrap();
-        assert_eq!(rucksack.priority(), Some(20));
+        assert_eq!(rucksack.first_shared_item().map(ItemType::priority), Some(20));
     }
 
     #[test]
     fn sixth_rucksack() {
         let rucksack = Rucksack::from("CrZsJsPPZsGzwwsLwLmpwMDw").unwrap();
-        assert_eq!(rucksack.priority(), Some(19));
+        assert_eq!(rucksack.first_shared_item().map(ItemType::priority), Some(19));
     }
 
     #[test]
@@ -187,7 +825,7 @@ mod tests {
         let second = Rucksack::from("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL").unwrap();
         let third = Rucksack::from("PmmdzqPrVvPwwTWBwg").unwrap();
 
-        assert_eq!(Rucksack::badge_item_type(&first, &second, &third), Some('r'));
+        assert_eq!(Rucksack::badge_item_type(&[first, second, third]), ItemType::from_char('r'));
     }
 
     #[test]
@@ -196,6 +834,148 @@ mod tests {
         let second = Rucksack::from("ttgJtRGJQctTZtZT").unwrap();
         let third = Rucksack::from("CrZsJsPPZsGzwwsLwLmpwMDw").unwrap();
 
-        assert_eq!(Rucksack::badge_item_type(&first, &second, &third), Some('Z'));
+        assert_eq!(Rucksack::badge_item_type(&[first, second, third]), ItemType::from_char('Z'));
+    }
+
+    #[test]
+    fn badge_of_a_pair() {
+        let first = Rucksack::from("abcD").unwrap();
+        let second = Rucksack::from("xDyz").unwrap();
+        assert_eq!(Rucksack::badge_item_type(&[first, second]), ItemType::from_char('D'));
+    }
+
+    #[test]
+    fn badge_of_an_empty_group() {
+        assert_eq!(Rucksack::badge_item_type(&[]), None);
+    }
+
+    #[test]
+    fn group_rucksacks_by_size() {
+        let rucksacks = parse_rucksacks("abca\nabdb\nefgh\nehij").unwrap();
+        let groups = groups(&rucksacks, 2).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(Rucksack::badge_item_type(groups[1]), ItemType::from_char('e'));
+    }
+
+    #[test]
+    fn reject_partial_group() {
+        let rucksacks = parse_rucksacks("abca\nabdb\nefgh\nehij").unwrap();
+        let error = groups(&rucksacks, 3).unwrap_err();
+        assert_eq!(error, GroupError::PartialGroup { first_line: 4, found: 1, expected: 3 });
+        assert_eq!(error.to_string(), "the group starting on line 4 has 1 rucksacks, expected 3");
+    }
+
+    #[test]
+    fn item_set_types_in_priority_order() {
+        assert_eq!(ItemSet::from("ZbAa").item_types(), item_types("abAZ"));
+    }
+
+    #[test]
+    fn analyse_shared_items() {
+        let rucksacks = parse_rucksacks("abca\nabab\nabcd\nxbyb").unwrap();
+        let groups = groups(&rucksacks, 2).unwrap();
+        let analysis = Analysis::from(&groups);
+        assert_eq!(analysis.rucksacks, vec![item_types("a"), item_types("ab"), vec![], item_types("b")]);
+        assert_eq!(analysis.groups, vec![item_types("ab"), item_types("b")]);
+        let report = analysis.to_string();
+        assert!(report.contains("line 2: a, b <- expected exactly one"));
+        assert!(report.contains("line 3: none <- expected exactly one"));
+        assert!(report.contains("lines 3-4: b\n"));
+        assert!(report.contains("2 of 4 rucksacks and 1 of 2 groups"));
+    }
+
+    #[test]
+    fn repack_a_single_misplaced_type() {
+        let rucksack = Rucksack::from("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+        let (repacked, swaps) = rucksack.repack().unwrap();
+        assert_eq!(swaps, 1);
+        assert_eq!(repacked.to_string().len(), 24);
+        assert_eq!(repacked.shared_items(), ItemSet(0));
+    }
+
+    #[test]
+    fn repack_keeps_a_valid_rucksack() {
+        let rucksack = Rucksack::from("abcdef").unwrap();
+        let (repacked, swaps) = rucksack.repack().unwrap();
+        assert_eq!(swaps, 0);
+        assert_eq!(repacked.to_string(), "abcdef");
+    }
+
+    #[test]
+    fn repack_moves_the_cheapest_types() {
+        let rucksack = Rucksack::from("aabacb").unwrap();
+        let (repacked, swaps) = rucksack.repack().unwrap();
+        assert_eq!(swaps, 1);
+        assert_eq!(repacked.to_string(), "aaabcb");
+    }
+
+    #[test]
+    fn repack_impossible_split() {
+        let rucksack = Rucksack::from("aaaa").unwrap();
+        assert_eq!(rucksack.repack(), None);
+    }
+
+    #[test]
+    fn reject_empty_groups() {
+        let rucksacks = parse_rucksacks("abca").unwrap();
+        assert_eq!(groups(&rucksacks, 0).unwrap_err(), GroupError::EmptyGroups);
+    }
+
+    #[test]
+    fn discover_shuffled_groups() {
+        let rucksacks = parse_rucksacks("ab\nxy\nac\nxz\nad\nxw").unwrap();
+        assert_eq!(GroupSearch::discover(&rucksacks, SEARCH_LIMIT), Discovery::Unique(vec![[0, 2, 4], [1, 3, 5]]));
+    }
+
+    #[test]
+    fn discover_example_groups() {
+        let rucksacks = parse_rucksacks("vJrwpWtwJgWrhcsFMMfFFhFp\nttgJtRGJQctTZtZT\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+            CrZsJsPPZsGzwwsLwLmpwMDw\nPmmdzqPrVvPwwTWBwg\nwMqvLMZHhHMvwLHjbvcjnnSBnvTQFn").unwrap();
+        assert_eq!(GroupSearch::discover(&rucksacks, SEARCH_LIMIT), Discovery::Ambiguous(vec![[0, 1, 3], [2, 4, 5]]));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn discover_ambiguous_groups() {
+        let rucksacks = parse_rucksacks("ab\nac\nad\nae\naf\nag").unwrap();
+        assert!(matches!(GroupSearch::discover(&rucksacks, SEARCH_LIMIT), Discovery::Ambiguous(_)));
+    }
+
+    #[test]
+    fn discover_impossible_groups() {
+        let rucksacks = parse_rucksacks("ab\nac\nad\nxy\nxz\nyz").unwrap();
+        assert_eq!(GroupSearch::discover(&rucksacks, SEARCH_LIMIT), Discovery::Impossible);
+        assert_eq!(GroupSearch::discover(&rucksacks[..4], SEARCH_LIMIT), Discovery::Impossible);
+    }
+
+    #[test]
+    fn discover_gives_up_at_the_limit() {
+        let rucksacks = parse_rucksacks("ab\nac\nad\nae\naf\nag").unwrap();
+        assert_eq!(GroupSearch::discover(&rucksacks, 1), Discovery::Undecided(None));
+    }
+
+    #[test]
+    fn rucksack_statistics() {
+        let rucksacks = parse_rucksacks("abca\nabab\nabcdef\nxbyb").unwrap();
+        let groups = groups(&rucksacks, 2).unwrap();
+        let statistics = Statistics::from(&rucksacks, &groups);
+        assert_eq!(&statistics.item_types[..2], &[(ItemType::from_char('a').unwrap(), 5, 2), (ItemType::from_char('b').unwrap(), 6, 2)]);
+        assert_eq!(statistics.sizes, vec![(4, 3), (6, 1)]);
+        assert_eq!(statistics.badges, vec![(ItemType::from_char('a').unwrap(), 1), (ItemType::from_char('b').unwrap(), 1)]);
+        assert_eq!(statistics.most_shared(), vec![(ItemType::from_char('a').unwrap(), 2), (ItemType::from_char('b').unwrap(), 2)]);
+        assert!(statistics.to_string().contains("Most often shared: a (2), b (2)\n"));
+    }
+
+    #[test]
+    fn rucksack_statistics_as_json() {
+        let rucksacks = parse_rucksacks("abca\nadae").unwrap();
+        let groups = groups(&rucksacks, 2).unwrap();
+        assert_eq!(Statistics::from(&rucksacks, &groups).json(), "{\"item_types\":[\
+            {\"type\":\"a\",\"priority\":1,\"items\":4,\"shared\":2},\
+            {\"type\":\"b\",\"priority\":2,\"items\":1,\"shared\":0},\
+            {\"type\":\"c\",\"priority\":3,\"items\":1,\"shared\":0},\
+            {\"type\":\"d\",\"priority\":4,\"items\":1,\"shared\":0},\
+            {\"type\":\"e\",\"priority\":5,\"items\":1,\"shared\":0}],\
+            \"most_shared\":[\"a\"],\"rucksack_sizes\":[{\"size\":4,\"rucksacks\":2}],\
+            \"badges\":[{\"type\":\"a\",\"groups\":1,\"priority_total\":1}]}\n");
+    }
+}
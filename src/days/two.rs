@@ -1,31 +1,723 @@
-use std::{fmt, fs};
+use std::{fmt, fs, io};
+use std::cell::OnceCell;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::cmp::Reverse;
 use std::fmt::Formatter;
-use advent2022::DailyChallenge;
+use advent2022::{Arguments, DailyChallenge};
 use crate::days::two::Choice::{Paper, Rock, Scissors};
+use crate::days::two::tournament::Strategy;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+mod generator;
+mod tournament;
+
+const PART_ONE_SCHEMA: &str = "\
+opponent A = Rock
+opponent B = Paper
+opponent C = Scissors
+response X = Rock
+response Y = Paper
+response Z = Scissors";
+
+const PART_TWO_SCHEMA: &str = "\
+opponent A = Rock
+opponent B = Paper
+opponent C = Scissors
+response X = Lose
+response Y = Draw
+response Z = Win";
+
+lazy_static! {
+    static ref CLASSIC: CyclicGame = CyclicGame::rock_paper_scissors();
+    static ref PART_ONE: DecodingSchema = DecodingSchema::from(PART_ONE_SCHEMA).unwrap();
+    static ref PART_TWO: DecodingSchema = DecodingSchema::from(PART_TWO_SCHEMA).unwrap();
+}
+
+const PERMUTATIONS: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+
+pub struct Two {
+    parse_mode: ParseMode,
+    schema_path: Option<String>,
+    infer: bool,
+    cycle: Option<String>,
+    report: bool,
+    ledger_path: Option<String>,
+    scoring_path: Option<String>,
+    tournament_rounds: Result<Option<usize>, String>,
+    seed: Result<Option<u64>, String>,
+    generate_rounds: Result<Option<usize>, String>,
+    target: Result<Option<u32>, String>,
+    alternate: bool
+}
 
-pub struct Two {}
+impl Two {
+    pub fn from(arguments: &Arguments) -> Two {
+        let parse_mode = if arguments.flag("--lenient") {
+            ParseMode::Lenient
+        } else {
+            ParseMode::Strict
+        };
+        let schema_path = arguments.value("--schema").map(String::from);
+        let infer = arguments.flag("--infer");
+        let cycle = arguments.value("--cycle").map(String::from);
+        let report = arguments.flag("--report");
+        let ledger_path = arguments.value("--ledger").map(String::from);
+        let scoring_path = arguments.value("--scoring").map(String::from);
+        let tournament_rounds = arguments.number("--tournament");
+        let seed = arguments.number("--seed");
+        let generate_rounds = arguments.number("--generate");
+        let target = arguments.number("--target");
+        let alternate = arguments.flag("--alternate");
+        Two {
+            parse_mode,
+            schema_path,
+            infer,
+            cycle,
+            report,
+            ledger_path,
+            scoring_path,
+            tournament_rounds,
+            seed,
+            generate_rounds,
+            target,
+            alternate
+        }
+    }
+}
 
 impl DailyChallenge for Two {
     fn run(&self) {
         println!("Day Two");
-        let results: Vec<Game> = fs::read_to_string("contents/day_two.txt")
-            .expect("Should have been able to read file")
-            .lines()
-            // .take(10)
-            .flat_map(|line| Game::from(line))
-            .collect();
-        let part_one_results: Vec<u32> = results.
-            iter()
-            .map(|game| game.score() as u32)
-            .collect();
-        let part_one: u32 = part_one_results.iter().sum();
-        println!("The result of part one is {:?}", part_one);
-        let part_two_results: Vec<u32> = results
+        let (tournament_rounds, seed, generate_rounds, target) =
+            match (&self.tournament_rounds, &self.seed, &self.generate_rounds, &self.target) {
+                (Ok(tournament_rounds), Ok(seed), Ok(generate_rounds), Ok(target)) => {
+                    (*tournament_rounds, seed.unwrap_or(2022), *generate_rounds, *target)
+                }
+                (Err(error), _, _, _) | (_, Err(error), _, _) | (_, _, Err(error), _) | (_, _, _, Err(error)) => {
+                    println!("Unable to read the options: {}", error);
+                    return;
+                }
+            };
+        let contents = OnceCell::new();
+        let contents = || contents.get_or_init(|| fs::read_to_string("contents/day_two.txt")
+            .expect("Should have been able to read file"));
+        let scoring = match &self.scoring_path {
+            Some(scoring_path) => {
+                let scoring = fs::read_to_string(scoring_path)
+                    .map_err(|error| error.to_string())
+                    .and_then(|config| ScoringTable::from(&config));
+                match scoring {
+                    Ok(scoring) => scoring,
+                    Err(error) => {
+                        println!("Unable to read the scoring table in {}: {}", scoring_path, error);
+                        return;
+                    }
+                }
+            }
+            None => ScoringTable::standard(),
+        };
+        let totals = File::open("contents/day_two.txt")
+            .map_err(StreamError::from)
+            .and_then(|file| fast_totals(BufReader::new(file), &scoring, self.parse_mode));
+        match totals {
+            Ok((part_one, part_two)) => {
+                println!("The result of part one is {:?}", part_one);
+                println!("The result of part two is {:?}", part_two);
+            }
+            Err(error) => println!("Unable to read the strategy guide: {}", error),
+        }
+        if let Some(schema_path) = &self.schema_path {
+            let schema = fs::read_to_string(schema_path)
+                .map_err(|error| error.to_string())
+                .and_then(|config| DecodingSchema::from(&config));
+            match schema {
+                Ok(schema) => match self.total_score(contents(), &schema, &scoring) {
+                    Ok(total) => println!("The result using the schema in {} is {:?}", schema_path, total),
+                    Err(error) => println!("Unable to read the strategy guide: {}", error),
+                },
+                Err(error) => println!("Unable to read the schema in {}: {}", schema_path, error),
+            }
+        }
+        if self.infer {
+            match interpretations(contents(), &scoring, self.parse_mode) {
+                Ok(interpretations) => {
+                    println!("Interpretations of X, Y and Z ranked by total score");
+                    for (rank, interpretation) in interpretations.iter().enumerate() {
+                        let marker = if rank == 0 { " <- highest score" } else { "" };
+                        println!("{:>2}. {}{}", rank + 1, interpretation, marker);
+                    }
+                }
+                Err(error) => println!("Unable to read the strategy guide: {}", error),
+            }
+        }
+        if let Some(cycle) = &self.cycle {
+            let game = match cycle.as_str() {
+                "rps" => Ok(CyclicGame::rock_paper_scissors()),
+                "rpsls" => Ok(CyclicGame::rock_paper_scissors_lizard_spock()),
+                names => CyclicGame::from(&names.split(',').collect::<Vec<&str>>()),
+            };
+            match game {
+                Ok(game) => print!("{}", game),
+                Err(error) => println!("Unable to build the game {}: {}", cycle, error),
+            }
+        }
+        if self.report || self.ledger_path.is_some() {
+            match (self.breakdown(contents(), &PART_ONE, &scoring), self.breakdown(contents(), &PART_TWO, &scoring)) {
+                (Ok(part_one), Ok(part_two)) => {
+                    if self.report {
+                        println!("Part one breakdown");
+                        print!("{}", part_one);
+                        println!("Part two breakdown");
+                        print!("{}", part_two);
+                    }
+                    if let Some(ledger_path) = &self.ledger_path {
+                        let csv = format!("{}{}{}", Report::CSV_HEADER, part_one.csv("part one"), part_two.csv("part two"));
+                        match fs::write(ledger_path, csv) {
+                            Ok(()) => println!("Wrote the score ledger to {}", ledger_path),
+                            Err(error) => println!("Unable to write the score ledger to {}: {}", ledger_path, error),
+                        }
+                    }
+                }
+                (Err(error), _) | (_, Err(error)) => println!("Unable to read the strategy guide: {}", error),
+            }
+        }
+        if let Some(rounds) = tournament_rounds {
+            match parse_guide(contents(), &PART_ONE, self.parse_mode) {
+                Ok(games) => {
+                    let strategies = [
+                        Strategy::AlwaysRock,
+                        Strategy::Cycle,
+                        Strategy::CopyLast,
+                        Strategy::FrequencyCounter,
+                        Strategy::ReplayGuide(games.iter().map(|game| game.recommended).collect()),
+                    ];
+                    println!("Round robin tournament of {} rounds per match with seed {}", rounds, seed);
+                    print!("{}", tournament::round_robin(&strategies, rounds, seed, &scoring));
+                }
+                Err(error) => println!("Unable to read the strategy guide: {}", error),
+            }
+        }
+        if let Some(rounds) = generate_rounds {
+            let (schema, part) = if self.alternate { (&*PART_TWO, "two") } else { (&*PART_ONE, "one") };
+            match target {
+                Some(target) => match generator::generate(rounds, target, schema, &scoring, seed) {
+                    Ok(guide) => {
+                        println!("Strategy guide of {} rounds scoring {} under part {}", rounds, target, part);
+                        for line in guide {
+                            println!("{}", line);
+                        }
+                    }
+                    Err(error) => println!("Unable to generate a strategy guide: {}", error),
+                },
+                None => println!("Unable to generate a strategy guide: --target is required"),
+            }
+        }
+    }
+}
+
+impl Two {
+    fn total_score(&self, contents: &str, schema: &DecodingSchema, scoring: &ScoringTable) -> Result<u64, GuideError> {
+        let results = parse_guide(contents, schema, self.parse_mode)?;
+        let scores: Vec<u64> = results
             .iter()
-            .map(|game| game.alternate_score() as u32)
+            .map(|game| game.score(scoring) as u64)
             .collect();
-        let part_two: u32 = part_two_results.iter().sum();
-        println!("The result of part two is {:?}", part_two);
+        Ok(scores.iter().sum())
+    }
+
+    fn breakdown(&self, contents: &str, schema: &DecodingSchema, scoring: &ScoringTable) -> Result<Report, GuideError> {
+        parse_rounds(contents, schema, self.parse_mode).map(|rounds| Report::from(rounds, scoring))
+    }
+}
+
+fn interpretations(contents: &str, scoring: &ScoringTable, parse_mode: ParseMode) -> Result<Vec<Interpretation>, GuideError> {
+    let moves = [Response::Play(Rock), Response::Play(Paper), Response::Play(Scissors)];
+    let outcomes = [
+        Response::Aim(MatchResult::Lose),
+        Response::Aim(MatchResult::Draw),
+        Response::Aim(MatchResult::Win)
+    ];
+
+    let mut interpretations = Vec::new();
+    for meanings in [moves, outcomes] {
+        for permutation in PERMUTATIONS {
+            let responses = permutation.map(|index| meanings[index]);
+            let schema = DecodingSchema {
+                opponent: PART_ONE.opponent.clone(),
+                response: ["X", "Y", "Z"].iter()
+                    .zip(responses)
+                    .map(|(code, response)| (String::from(*code), response))
+                    .collect(),
+            };
+            let games = parse_guide(contents, &schema, parse_mode)?;
+            interpretations.push(Interpretation::from(responses, &games, scoring));
+        }
+    }
+    interpretations.sort_by_key(|interpretation| Reverse(interpretation.total));
+    Ok(interpretations)
+}
+
+#[derive(Debug, PartialEq)]
+struct Interpretation { responses: [Response; 3], total: u64, wins: usize, losses: usize }
+
+impl Interpretation {
+    fn from(responses: [Response; 3], games: &[Game], scoring: &ScoringTable) -> Interpretation {
+        Interpretation {
+            responses,
+            total: games.iter().map(|game| game.score(scoring) as u64).sum(),
+            wins: games.iter().filter(|game| game.result() == MatchResult::Win).count(),
+            losses: games.iter().filter(|game| game.result() == MatchResult::Lose).count(),
+        }
+    }
+}
+
+impl fmt::Display for Interpretation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "X={} Y={} Z={} scores {} with {} wins and {} losses",
+               self.responses[0], self.responses[1], self.responses[2], self.total, self.wins, self.losses)
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum ParseMode {
+    Strict,
+    Lenient
+}
+
+fn parse_guide(contents: &str, schema: &DecodingSchema, parse_mode: ParseMode) -> Result<Vec<Game>, GuideError> {
+    let rounds = parse_rounds(contents, schema, parse_mode)?;
+    Ok(rounds.iter().map(|round| round.game).collect())
+}
+
+fn parse_rounds(contents: &str, schema: &DecodingSchema, parse_mode: ParseMode) -> Result<Vec<Round>, GuideError> {
+    let mut rounds = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        match schema.game(line) {
+            Ok(game) => rounds.push(Round { line_number: index + 1, game }),
+            Err(cause) => {
+                let error = GuideError { line_number: index + 1, cause };
+                match parse_mode {
+                    ParseMode::Strict => return Err(error),
+                    ParseMode::Lenient => eprintln!("Skipping {}", error),
+                }
+            }
+        }
+    }
+    Ok(rounds)
+}
+
+fn fast_totals<R: BufRead>(mut reader: R, scoring: &ScoringTable, parse_mode: ParseMode) -> Result<(u64, u64), StreamError> {
+    let mut table = [(0, 0); 9];
+    for (index, entry) in table.iter_mut().enumerate() {
+        let line = format!("{} {}", (b'A' + index as u8 / 3) as char, (b'X' + index as u8 % 3) as char);
+        let part_one = PART_ONE.game(&line).expect("Part one schema should decode every line");
+        let part_two = PART_TWO.game(&line).expect("Part two schema should decode every line");
+        *entry = (part_one.score(scoring) as u64, part_two.score(scoring) as u64);
+    }
+
+    let (mut part_one, mut part_two) = (0, 0);
+    let mut buffer = Vec::new();
+    let mut line_number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        line_number += 1;
+        let line = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        match line {
+            [opponent @ b'A'..=b'C', b' ', response @ b'X'..=b'Z'] => {
+                let (score, alternate_score) = table[(opponent - b'A') as usize * 3 + (response - b'X') as usize];
+                part_one += score;
+                part_two += alternate_score;
+            }
+            _ => {
+                if let Err(cause) = PART_ONE.game(&String::from_utf8_lossy(line)) {
+                    let error = GuideError { line_number, cause };
+                    match parse_mode {
+                        ParseMode::Strict => return Err(StreamError::Guide(error)),
+                        ParseMode::Lenient => eprintln!("Skipping {}", error),
+                    }
+                }
+            }
+        }
+    }
+    Ok((part_one, part_two))
+}
+
+#[derive(Debug)]
+enum StreamError {
+    Read(io::Error),
+    Guide(GuideError)
+}
+
+impl From<io::Error> for StreamError {
+    fn from(error: io::Error) -> Self {
+        StreamError::Read(error)
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Read(error) => write!(f, "{}", error),
+            StreamError::Guide(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct Round { line_number: usize, game: Game }
+
+#[derive(Debug, PartialEq)]
+struct LedgerRow { round: Round, score: u32, total: u64 }
+
+impl fmt::Display for LedgerRow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>5}: {} scores {} (total {})", self.round.line_number, self.round.game, self.score, self.total)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Report {
+    wins: usize,
+    draws: usize,
+    losses: usize,
+    shape_points: u64,
+    outcome_points: u64,
+    recommended_moves: [usize; 3],
+    opponent_moves: [usize; 3],
+    ledger: Vec<LedgerRow>,
+}
+
+impl Report {
+    const CSV_HEADER: &'static str = "interpretation,line,opponent,recommended,result,score,total\n";
+
+    fn from(rounds: Vec<Round>, scoring: &ScoringTable) -> Report {
+        let mut report = Report {
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            shape_points: 0,
+            outcome_points: 0,
+            recommended_moves: [0; 3],
+            opponent_moves: [0; 3],
+            ledger: vec![],
+        };
+        let mut total = 0;
+        for round in rounds {
+            let game = round.game;
+            match game.result() {
+                MatchResult::Win => report.wins += 1,
+                MatchResult::Draw => report.draws += 1,
+                MatchResult::Lose => report.losses += 1,
+            }
+            report.shape_points += scoring.shape(game.recommended) as u64;
+            report.outcome_points += scoring.outcome(game.result()) as u64;
+            report.recommended_moves[game.recommended.index()] += 1;
+            report.opponent_moves[game.opponent.index()] += 1;
+            let score = game.score(scoring);
+            total += score as u64;
+            report.ledger.push(LedgerRow { round, score, total });
+        }
+        report
+    }
+
+    fn csv(&self, interpretation: &str) -> String {
+        self.ledger
+            .iter()
+            .map(|row| format!("{},{},{:?},{:?},{:?},{},{}\n",
+                               interpretation, row.round.line_number, row.round.game.opponent,
+                               row.round.game.recommended, row.round.game.result(), row.score, row.total))
+            .collect()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} wins, {} draws and {} losses", self.wins, self.draws, self.losses)?;
+        writeln!(f, "{} points from shapes and {} points from outcomes", self.shape_points, self.outcome_points)?;
+        writeln!(f, "{:<10}{:>8}{:>10}", "Move", "Played", "Opponent")?;
+        for index in 0..3 {
+            let choice = format!("{:?}", Choice::from_index(index));
+            writeln!(f, "{:<10}{:>8}{:>10}", choice, self.recommended_moves[index], self.opponent_moves[index])?;
+        }
+        for row in &self.ledger {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct TokenError { position: usize, token: String, reason: &'static str }
+
+#[derive(Debug, PartialEq)]
+struct GuideError { line_number: usize, cause: TokenError }
+
+impl fmt::Display for GuideError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {} (found {:?})",
+               self.line_number, self.cause.position, self.cause.reason, self.cause.token)
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Response {
+    Play(Choice),
+    Aim(MatchResult)
+}
+
+impl Response {
+    fn from(meaning: &str) -> Result<Response, &'static str> {
+        match meaning {
+            "Rock" => Ok(Response::Play(Rock)),
+            "Paper" => Ok(Response::Play(Paper)),
+            "Scissors" => Ok(Response::Play(Scissors)),
+            "Win" => Ok(Response::Aim(MatchResult::Win)),
+            "Draw" => Ok(Response::Aim(MatchResult::Draw)),
+            "Lose" => Ok(Response::Aim(MatchResult::Lose)),
+            _ => Err("unknown meaning"),
+        }
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::Play(choice) => write!(f, "{:?}", choice),
+            Response::Aim(result) => write!(f, "{:?}", result),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct DecodingSchema {
+    opponent: Vec<(String, Choice)>,
+    response: Vec<(String, Response)>,
+}
+
+impl DecodingSchema {
+    fn from(config: &str) -> Result<DecodingSchema, String> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"^(opponent|response) (\S+) = (\w+)$").unwrap();
+        }
+
+        let mut schema = DecodingSchema { opponent: vec![], response: vec![] };
+        for (index, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let capture = RE.captures(line)
+                .ok_or(format!("line {}: expected '<opponent|response> <code> = <meaning>'", index + 1))?;
+            let code = String::from(&capture[2]);
+            let response = Response::from(&capture[3])
+                .map_err(|reason| format!("line {}: {} '{}'", index + 1, reason, &capture[3]))?;
+            let declared = match &capture[1] {
+                "opponent" => schema.opponent.iter().any(|(known, _)| *known == code),
+                _ => schema.response.iter().any(|(known, _)| *known == code),
+            };
+            if declared {
+                return Err(format!("line {}: code '{}' is declared twice", index + 1, code));
+            }
+            match (&capture[1], response) {
+                ("opponent", Response::Play(choice)) => schema.opponent.push((code, choice)),
+                ("opponent", Response::Aim(_)) => {
+                    return Err(format!("line {}: the opponent column can only contain moves", index + 1));
+                }
+                _ => schema.response.push((code, response)),
+            }
+        }
+
+        if schema.opponent.is_empty() || schema.response.is_empty() {
+            return Err(String::from("both the opponent and response columns need at least one code"));
+        }
+        Ok(schema)
+    }
+
+    fn decode(&self, line: &str) -> Result<(Choice, Response), TokenError> {
+        fn lookup<T: Copy>(codes: &[(String, T)], token: &str, position: usize, reason: &'static str) -> Result<T, TokenError> {
+            if token.is_empty() {
+                return Err(TokenError { position, token: String::new(), reason: "empty token, expected a single space between columns" });
+            }
+            codes.iter()
+                .find(|(code, _)| code == token)
+                .map(|(_, meaning)| *meaning)
+                .ok_or(TokenError { position, token: String::from(token), reason })
+        }
+
+        let mut opponent = None;
+        let mut response = None;
+        let mut position = 1;
+        for (index, token) in line.split(' ').enumerate() {
+            match index {
+                0 => opponent = Some(lookup(&self.opponent, token, position, "unknown opponent code")?),
+                1 => response = Some(lookup(&self.response, token, position, "unknown response code")?),
+                _ => return Err(TokenError { position, token: String::from(token), reason: "unexpected extra token" }),
+            }
+            position += token.chars().count() + 1;
+        }
+
+        match (opponent, response) {
+            (Some(opponent), Some(response)) => Ok((opponent, response)),
+            _ => Err(TokenError { position, token: String::new(), reason: "missing response" }),
+        }
+    }
+
+    fn game(&self, line: &str) -> Result<Game, TokenError> {
+        let (opponent, response) = self.decode(line)?;
+        let recommended = match response {
+            Response::Play(choice) => choice,
+            Response::Aim(result) => Choice::required_for(opponent, result),
+        };
+        Ok(Game { opponent, recommended })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ScoringTable { shapes: [u32; 3], outcomes: [u32; 3] }
+
+impl ScoringTable {
+    const OUTCOMES: [MatchResult; 3] = [MatchResult::Win, MatchResult::Draw, MatchResult::Lose];
+
+    fn standard() -> ScoringTable {
+        ScoringTable {
+            shapes: [Rock, Paper, Scissors].map(|choice| choice.score() as u32),
+            outcomes: ScoringTable::OUTCOMES.map(|result| result.score() as u32),
+        }
+    }
+
+    fn from(config: &str) -> Result<ScoringTable, String> {
+        let mut shapes = [None; 3];
+        let mut outcomes = [None; 3];
+        for (index, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, points) = line.split_once('=')
+                .ok_or(format!("line {}: expected '<move or outcome> = <points>'", index + 1))?;
+            let (name, points) = (name.trim(), points.trim());
+            let points = points.parse::<u32>()
+                .map_err(|_| format!("line {}: invalid points '{}'", index + 1, points))?;
+            let entry = match Response::from(name) {
+                Ok(Response::Play(choice)) => &mut shapes[choice.index()],
+                Ok(Response::Aim(result)) => &mut outcomes[ScoringTable::outcome_index(result)],
+                Err(reason) => return Err(format!("line {}: {} '{}'", index + 1, reason, name)),
+            };
+            if entry.replace(points).is_some() {
+                return Err(format!("line {}: '{}' is scored twice", index + 1, name));
+            }
+        }
+
+        let mut missing: Vec<String> = vec![];
+        for (index, points) in shapes.iter().enumerate() {
+            if points.is_none() {
+                missing.push(format!("{:?}", Choice::from_index(index)));
+            }
+        }
+        for (index, points) in outcomes.iter().enumerate() {
+            if points.is_none() {
+                missing.push(format!("{:?}", ScoringTable::OUTCOMES[index]));
+            }
+        }
+        if !missing.is_empty() {
+            return Err(format!("no points given for {}", missing.join(", ")));
+        }
+        let scoring = ScoringTable {
+            shapes: shapes.map(|points| points.unwrap_or_default()),
+            outcomes: outcomes.map(|points| points.unwrap_or_default()),
+        };
+        let highest = scoring.shapes.iter().max().unwrap_or(&0).checked_add(*scoring.outcomes.iter().max().unwrap_or(&0));
+        if highest.is_none() {
+            return Err(format!("the highest move and outcome points add up to more than {}", u32::MAX));
+        }
+        Ok(scoring)
+    }
+
+    fn outcome_index(result: MatchResult) -> usize {
+        match result {
+            MatchResult::Win => 0,
+            MatchResult::Draw => 1,
+            MatchResult::Lose => 2,
+        }
+    }
+
+    fn shape(&self, choice: Choice) -> u32 {
+        self.shapes[choice.index()]
+    }
+
+    fn outcome(&self, result: MatchResult) -> u32 {
+        self.outcomes[ScoringTable::outcome_index(result)]
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct CyclicGame { moves: Vec<String> }
+
+impl CyclicGame {
+    fn from(moves: &[&str]) -> Result<CyclicGame, &'static str> {
+        if moves.len() < 3 {
+            return Err("a cyclic game needs at least three moves");
+        }
+        if moves.len().is_multiple_of(2) {
+            return Err("a cyclic game needs an odd number of moves");
+        }
+        if moves.iter().any(|name| name.is_empty()) {
+            return Err("move names cannot be empty");
+        }
+        if moves.iter().enumerate().any(|(index, name)| moves[..index].contains(name)) {
+            return Err("move names must be unique");
+        }
+        Ok(CyclicGame { moves: moves.iter().map(|name| String::from(*name)).collect() })
+    }
+
+    fn rock_paper_scissors() -> CyclicGame {
+        CyclicGame::from(&["Rock", "Paper", "Scissors"]).unwrap()
+    }
+
+    fn rock_paper_scissors_lizard_spock() -> CyclicGame {
+        CyclicGame::from(&["Rock", "Spock", "Paper", "Lizard", "Scissors"]).unwrap()
+    }
+
+    fn shape_score(&self, played: usize) -> u32 {
+        played as u32 + 1
+    }
+
+    // Each move beats the (n - 1) / 2 moves before it in the cycle and loses to the ones after it.
+    fn result(&self, played: usize, opponent: usize) -> MatchResult {
+        let size = self.moves.len();
+        match (played + size - opponent) % size {
+            0 => MatchResult::Draw,
+            distance if distance <= size / 2 => MatchResult::Win,
+            _ => MatchResult::Lose,
+        }
+    }
+
+    fn required_move(&self, opponent: usize, result: MatchResult) -> usize {
+        let size = self.moves.len();
+        match result {
+            MatchResult::Win => (opponent + 1) % size,
+            MatchResult::Draw => opponent,
+            MatchResult::Lose => (opponent + size - 1) % size,
+        }
+    }
+}
+
+impl fmt::Display for CyclicGame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (played, name) in self.moves.iter().enumerate() {
+            let beaten: Vec<&str> = (0..self.moves.len())
+                .filter(|&opponent| self.result(played, opponent) == MatchResult::Win)
+                .map(|opponent| self.moves[opponent].as_str())
+                .collect();
+            writeln!(f, "{} (shape score {}) beats {}", name, self.shape_score(played), beaten.join(", "))?;
+        }
+        Ok(())
     }
 }
 
@@ -37,28 +729,28 @@ enum Choice {
 }
 
 impl Choice {
-    fn from(code: &str) -> Result<Choice, &'static str> {
-        match code {
-            "A" => Ok(Rock),
-            "B" => Ok(Paper),
-            "C" => Ok(Scissors),
-            "X" => Ok(Rock),
-            "Y" => Ok(Paper),
-            "Z" => Ok(Scissors),
-            _ => Err("Unknown Type"),
+    fn index(&self) -> usize {
+        match self {
+            Rock => 0,
+            Paper => 1,
+            Scissors => 2,
         }
     }
 
+    fn from_index(index: usize) -> Choice {
+        [Rock, Paper, Scissors][index]
+    }
+
     fn score(&self) -> u8 {
-        match self {
-            Rock => 1,
-            Paper => 2,
-            Scissors => 3,
-        }
+        CLASSIC.shape_score(self.index()) as u8
+    }
+
+    fn required_for(opponent: Choice, result: MatchResult) -> Choice {
+        Choice::from_index(CLASSIC.required_move(opponent.index(), result))
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 enum MatchResult {
     Win,
     Draw,
@@ -75,64 +767,16 @@ impl MatchResult {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 struct Game { opponent: Choice, recommended: Choice }
 
 impl Game {
-    fn from(line: &str) -> Result<Game, &'static str> {
-        let parts: Vec<Choice> = line
-            .split(" ")
-            .flat_map(|choice| Choice::from(choice))
-            .collect();
-
-        match parts.as_slice() {
-            [opponent, recommended] => {
-                return Ok(Game { opponent: *opponent, recommended: *recommended});
-            },
-            _ => return Err("Invalid Game Line"),
-        }
-    }
-
     fn result(&self) -> MatchResult {
-        match (self.recommended, self.opponent) {
-            (Rock, Scissors) => MatchResult::Win,
-            (Rock, Paper) => MatchResult::Lose,
-            (Paper, Rock) => MatchResult::Win,
-            (Paper, Scissors) => MatchResult::Lose,
-            (Scissors, Rock) => MatchResult::Lose,
-            (Scissors, Paper) => MatchResult::Win,
-            _ => MatchResult::Draw,
-        }
-    }
-
-    fn score(&self) -> u8 {
-        self.recommended.score() + self.result().score()
+        CLASSIC.result(self.recommended.index(), self.opponent.index())
     }
 
-    fn alternate_recommendation(&self) -> MatchResult {
-        match self.recommended {
-            Rock => MatchResult::Lose,
-            Paper => MatchResult::Draw,
-            Scissors => MatchResult::Win,
-        }
-    }
-
-    fn alternate_result(&self) -> Choice {
-        match (self.opponent, self.alternate_recommendation()) {
-            (Rock, MatchResult::Win) => Paper,
-            (Rock, MatchResult::Lose) => Scissors,
-            (Rock, MatchResult::Draw) => Rock,
-            (Paper, MatchResult::Win) => Scissors,
-            (Paper, MatchResult::Lose) => Rock,
-            (Paper, MatchResult::Draw) => Paper,
-            (Scissors, MatchResult::Win) => Rock,
-            (Scissors, MatchResult::Lose) => Paper,
-            (Scissors, MatchResult::Draw) => Scissors,
-        }
-    }
-
-    fn alternate_score(&self) -> u8 {
-        self.alternate_result().score() + self.alternate_recommendation().score()
+    fn score(&self, scoring: &ScoringTable) -> u32 {
+        scoring.shape(self.recommended) + scoring.outcome(self.result())
     }
 }
 
@@ -149,37 +793,38 @@ mod tests {
 
     #[test]
     fn decode_unkown_choice() {
-        assert_eq!(Err("Unknown Type"), Choice::from("R"));
+        let expected = TokenError { position: 1, token: String::from("R"), reason: "unknown opponent code" };
+        assert_eq!(Err(expected), PART_ONE.decode("R X"));
     }
 
     #[test]
     fn decode_a_to_rock() {
-        assert_eq!(Ok(Choice::Rock), Choice::from("A"));
+        assert_eq!(Ok(Choice::Rock), PART_ONE.decode("A X").map(|(opponent, _)| opponent));
     }
 
     #[test]
     fn decode_b_to_paper() {
-        assert_eq!(Ok(Choice::Paper), Choice::from("B"));
+        assert_eq!(Ok(Choice::Paper), PART_ONE.decode("B X").map(|(opponent, _)| opponent));
     }
 
     #[test]
     fn decode_c_to_scissors() {
-        assert_eq!(Ok(Choice::Scissors), Choice::from("C"));
+        assert_eq!(Ok(Choice::Scissors), PART_ONE.decode("C X").map(|(opponent, _)| opponent));
     }
 
     #[test]
     fn decode_x_to_rock() {
-        assert_eq!(Ok(Choice::Rock), Choice::from("X"));
+        assert_eq!(Ok(Response::Play(Choice::Rock)), PART_ONE.decode("A X").map(|(_, response)| response));
     }
 
     #[test]
     fn decode_y_to_paper() {
-        assert_eq!(Ok(Choice::Paper), Choice::from("Y"));
+        assert_eq!(Ok(Response::Play(Choice::Paper)), PART_ONE.decode("A Y").map(|(_, response)| response));
     }
 
     #[test]
     fn decode_z_to_scissors() {
-        assert_eq!(Ok(Choice::Scissors), Choice::from("Z"));
+        assert_eq!(Ok(Response::Play(Choice::Scissors)), PART_ONE.decode("A Z").map(|(_, response)| response));
     }
 
     #[test]
@@ -199,14 +844,303 @@ mod tests {
 
     #[test]
     fn decode_invalid_line() {
-        assert_eq!(Err("Invalid Game Line"), Game::from("E E"));
+        let expected = TokenError { position: 1, token: String::from("E"), reason: "unknown opponent code" };
+        assert_eq!(Err(expected), PART_ONE.game("E E"));
+    }
+
+    #[test]
+    fn reject_unknown_token() {
+        let expected = TokenError { position: 3, token: String::from("Q"), reason: "unknown response code" };
+        assert_eq!(PART_ONE.game("A Q"), Err(expected));
+    }
+
+    #[test]
+    fn columns_count_characters_not_bytes() {
+        let schema = DecodingSchema::from("opponent é = Rock\nopponent B = Paper\nopponent C = Scissors\n\
+            response X = Rock\nresponse Y = Paper\nresponse Z = Scissors").unwrap();
+        let expected = TokenError { position: 3, token: String::from("Q"), reason: "unknown response code" };
+        assert_eq!(schema.game("é Q"), Err(expected));
+    }
+
+    #[test]
+    fn reject_double_space() {
+        let expected = TokenError {
+            position: 3,
+            token: String::new(),
+            reason: "empty token, expected a single space between columns"
+        };
+        assert_eq!(PART_ONE.game("A  Y"), Err(expected));
+    }
+
+    #[test]
+    fn reject_swapped_columns() {
+        let expected = TokenError { position: 1, token: String::from("X"), reason: "unknown opponent code" };
+        assert_eq!(PART_ONE.game("X A"), Err(expected));
+    }
+
+    #[test]
+    fn reject_missing_choice() {
+        let expected = TokenError { position: 3, token: String::new(), reason: "missing response" };
+        assert_eq!(PART_ONE.game("A"), Err(expected));
+    }
+
+    #[test]
+    fn reject_extra_token() {
+        let expected = TokenError { position: 5, token: String::from("Z"), reason: "unexpected extra token" };
+        assert_eq!(PART_ONE.game("A Y Z"), Err(expected));
+    }
+
+    #[test]
+    fn strict_guide_reports_line_number() {
+        let error = parse_guide("A Y\nB X\nC  Z", &PART_ONE, ParseMode::Strict).unwrap_err();
+        assert_eq!(error.line_number, 3);
+        assert_eq!(error.to_string(), "line 3, column 3: empty token, expected a single space between columns (found \"\")");
+    }
+
+    #[test]
+    fn lenient_guide_skips_bad_lines() {
+        let games = parse_guide("A Y\nA Q\nC Z", &PART_ONE, ParseMode::Lenient).unwrap();
+        assert_eq!(games.len(), 2);
+    }
+
+    #[test]
+    fn decode_custom_schema() {
+        let schema = DecodingSchema::from("# swapped\nopponent R = Rock\nopponent P = Paper\nresponse 1 = Scissors\nresponse 2 = Win").unwrap();
+        assert_eq!(schema.decode("R 2"), Ok((Rock, Response::Aim(MatchResult::Win))));
+        assert_eq!(schema.game("P 1"), Ok(Game { opponent: Paper, recommended: Scissors }));
+        assert_eq!(schema.game("P 2"), Ok(Game { opponent: Paper, recommended: Scissors }));
+    }
+
+    #[test]
+    fn reject_unknown_schema_meaning() {
+        let error = DecodingSchema::from("opponent A = Rock\nresponse X = Rok");
+        assert_eq!(error, Err(String::from("line 2: unknown meaning 'Rok'")));
+    }
+
+    #[test]
+    fn reject_outcome_for_opponent() {
+        let error = DecodingSchema::from("opponent A = Win\nresponse X = Rock");
+        assert_eq!(error, Err(String::from("line 1: the opponent column can only contain moves")));
+    }
+
+    #[test]
+    fn reject_duplicate_schema_code() {
+        let error = DecodingSchema::from("opponent A = Rock\nopponent A = Paper\nresponse X = Rock");
+        assert_eq!(error, Err(String::from("line 2: code 'A' is declared twice")));
+    }
+
+    #[test]
+    fn reject_schema_without_responses() {
+        assert!(DecodingSchema::from("opponent A = Rock").is_err());
+    }
+
+    #[test]
+    fn rank_interpretations_of_example() {
+        let interpretations = interpretations("A Y\nB X\nC Z", &ScoringTable::standard(), ParseMode::Strict).unwrap();
+        assert_eq!(interpretations.len(), 12);
+        let best = &interpretations[0];
+        assert_eq!(best.total, 24);
+        assert_eq!(best.wins, 3);
+        assert_eq!(best.losses, 0);
+        let part_one = interpretations.iter()
+            .find(|interpretation| interpretation.responses == [Response::Play(Rock), Response::Play(Paper), Response::Play(Scissors)])
+            .unwrap();
+        assert_eq!((part_one.total, part_one.wins, part_one.losses), (15, 1, 1));
+        let part_two = interpretations.iter()
+            .find(|interpretation| interpretation.responses == [
+                Response::Aim(MatchResult::Lose),
+                Response::Aim(MatchResult::Draw),
+                Response::Aim(MatchResult::Win)
+            ])
+            .unwrap();
+        assert_eq!((part_two.total, part_two.wins, part_two.losses), (12, 1, 1));
+    }
+
+    #[test]
+    fn report_breakdown_of_example() {
+        let rounds = parse_rounds("A Y\nB X\nC Z", &PART_ONE, ParseMode::Strict).unwrap();
+        let report = Report::from(rounds, &ScoringTable::standard());
+        assert_eq!((report.wins, report.draws, report.losses), (1, 1, 1));
+        assert_eq!((report.shape_points, report.outcome_points), (6, 9));
+        assert_eq!(report.recommended_moves, [1, 1, 1]);
+        assert_eq!(report.opponent_moves, [1, 1, 1]);
+        let totals: Vec<u64> = report.ledger.iter().map(|row| row.total).collect();
+        assert_eq!(totals, vec![8, 9, 15]);
+    }
+
+    #[test]
+    fn totals_beyond_u32() {
+        let scoring = ScoringTable::from("Rock = 1\nPaper = 2\nScissors = 3\nWin = 4000000000\nDraw = 3\nLose = 0").unwrap();
+        let interpretations = interpretations("A Y\nA Y\n", &scoring, ParseMode::Strict).unwrap();
+        assert_eq!(interpretations[0].total, 8000000004);
+        let rounds = parse_rounds("A Y\nA Y\n", &PART_ONE, ParseMode::Strict).unwrap();
+        let report = Report::from(rounds, &scoring);
+        assert_eq!((report.shape_points, report.outcome_points), (4, 8000000000));
+        assert_eq!(report.ledger[1].total, 8000000004);
+    }
+
+    #[test]
+    fn ledger_keeps_line_numbers_of_skipped_lines() {
+        let rounds = parse_rounds("A Y\nA Q\nC Z", &PART_TWO, ParseMode::Lenient).unwrap();
+        let report = Report::from(rounds, &ScoringTable::standard());
+        assert_eq!(report.ledger[1].to_string(), "    3: Rock vs Scissors = Win scores 7 (total 11)");
+        assert_eq!(report.csv("part two"), "part two,1,Rock,Rock,Draw,4,4\npart two,3,Scissors,Rock,Win,7,11\n");
+    }
+
+    #[test]
+    fn standard_scoring_matches_score() {
+        let scoring = ScoringTable::standard();
+        let lines = ["A X", "A Y", "A Z", "B X", "B Y", "B Z", "C X", "C Y", "C Z"];
+        let part_one = lines.map(|line| PART_ONE.game(line).unwrap().score(&scoring));
+        let part_two = lines.map(|line| PART_TWO.game(line).unwrap().score(&scoring));
+        assert_eq!(part_one, [4, 8, 3, 1, 5, 9, 7, 2, 6]);
+        assert_eq!(part_two, [3, 4, 8, 1, 5, 9, 2, 6, 7]);
+    }
+
+    #[test]
+    fn custom_scoring_table() {
+        let scoring = ScoringTable::from("Rock = 10\nPaper = 20\nScissors = 30\n# payouts\nWin = 100\nDraw = 50\nLose = 1").unwrap();
+        assert_eq!(PART_ONE.game("A Y").unwrap().score(&scoring), 120);
+        assert_eq!(PART_TWO.game("A Y").unwrap().score(&scoring), 60);
+    }
+
+    #[test]
+    fn reject_incomplete_scoring_table() {
+        let error = ScoringTable::from("Rock = 1\nPaper = 2\nWin = 6\nDraw = 3");
+        assert_eq!(error, Err(String::from("no points given for Scissors, Lose")));
+    }
+
+    #[test]
+    fn reject_duplicate_scoring_entry() {
+        let error = ScoringTable::from("Rock = 1\nRock = 2");
+        assert_eq!(error, Err(String::from("line 2: 'Rock' is scored twice")));
+    }
+
+    #[test]
+    fn reject_invalid_points() {
+        let error = ScoringTable::from("Rock = -1");
+        assert_eq!(error, Err(String::from("line 1: invalid points '-1'")));
+    }
+
+    #[test]
+    fn reject_scores_beyond_u32() {
+        let error = ScoringTable::from("Rock = 1\nPaper = 4294967295\nScissors = 3\nWin = 6\nDraw = 3\nLose = 0");
+        assert_eq!(error, Err(String::from("the highest move and outcome points add up to more than 4294967295")));
+        assert!(ScoringTable::from("Rock = 1\nPaper = 4294967289\nScissors = 3\nWin = 6\nDraw = 3\nLose = 0").is_ok());
+    }
+
+    #[test]
+    fn reject_unknown_scoring_name() {
+        let error = ScoringTable::from("Lizard = 4");
+        assert_eq!(error, Err(String::from("line 1: unknown meaning 'Lizard'")));
+    }
+
+    #[test]
+    fn fast_totals_match_game_path() {
+        let contents = fs::read_to_string("contents/day_two.txt").unwrap();
+        let scoring = ScoringTable::standard();
+        let part_one: u32 = parse_guide(&contents, &PART_ONE, ParseMode::Strict).unwrap().iter().map(|game| game.score(&scoring)).sum();
+        let part_two: u32 = parse_guide(&contents, &PART_TWO, ParseMode::Strict).unwrap().iter().map(|game| game.score(&scoring)).sum();
+        let totals = fast_totals(contents.as_bytes(), &scoring, ParseMode::Strict).unwrap();
+        assert_eq!(totals, (part_one as u64, part_two as u64));
+    }
+
+    #[test]
+    fn fast_totals_of_example() {
+        let scoring = ScoringTable::standard();
+        assert_eq!(fast_totals(&b"A Y\nB X\nC Z\n"[..], &scoring, ParseMode::Strict).unwrap(), (15, 12));
+        assert_eq!(fast_totals(&b"A Y\r\nB X\r\nC Z"[..], &scoring, ParseMode::Strict).unwrap(), (15, 12));
+        assert_eq!(fast_totals(&b""[..], &scoring, ParseMode::Strict).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn fast_totals_beyond_u32() {
+        let scoring = ScoringTable::from("Rock = 1\nPaper = 2\nScissors = 3\nWin = 4000000000\nDraw = 3\nLose = 0").unwrap();
+        let totals = fast_totals(&b"A Y\nA Y\n"[..], &scoring, ParseMode::Strict).unwrap();
+        assert_eq!(totals.0, 2 * (4000000000 + 2));
+    }
+
+    #[test]
+    fn fast_totals_skip_invalid_utf8_in_lenient_mode() {
+        let scoring = ScoringTable::standard();
+        assert_eq!(fast_totals(&b"A Y\n\xff\xfe\nC Z"[..], &scoring, ParseMode::Lenient).unwrap(), (14, 11));
+    }
+
+    #[test]
+    fn fast_totals_report_malformed_lines() {
+        let scoring = ScoringTable::standard();
+        match fast_totals(&b"A Y\nB  X\nC Z"[..], &scoring, ParseMode::Strict) {
+            Err(StreamError::Guide(error)) => {
+                assert_eq!(error.line_number, 2);
+                assert_eq!(error.cause.position, 3);
+            }
+            other => panic!("expected a guide error, found {:?}", other),
+        }
+        assert_eq!(fast_totals(&b"A Y\nA Q\nC Z"[..], &scoring, ParseMode::Lenient).unwrap(), (14, 11));
+    }
+
+    #[test]
+    fn reject_even_sized_cycle() {
+        assert_eq!(CyclicGame::from(&["Rock", "Paper", "Scissors", "Lizard"]), Err("a cyclic game needs an odd number of moves"));
+    }
+
+    #[test]
+    fn reject_single_move_cycle() {
+        assert_eq!(CyclicGame::from(&["Rock"]), Err("a cyclic game needs at least three moves"));
+    }
+
+    #[test]
+    fn reject_duplicate_moves() {
+        assert_eq!(CyclicGame::from(&["Rock", "Paper", "Rock"]), Err("move names must be unique"));
+    }
+
+    #[test]
+    fn shape_scores_beyond_a_byte() {
+        let names: Vec<String> = (0..301).map(|index| format!("move{}", index)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let game = CyclicGame::from(&names).unwrap();
+        assert_eq!(game.shape_score(300), 301);
+    }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock_rules() {
+        let game = CyclicGame::rock_paper_scissors_lizard_spock();
+        let index = |name: &str| game.moves.iter().position(|known| known == name).unwrap();
+        let wins = [
+            ("Scissors", "Paper"), ("Paper", "Rock"), ("Rock", "Lizard"), ("Lizard", "Spock"), ("Spock", "Scissors"),
+            ("Scissors", "Lizard"), ("Lizard", "Paper"), ("Paper", "Spock"), ("Spock", "Rock"), ("Rock", "Scissors")
+        ];
+        for (winner, loser) in wins {
+            assert_eq!(game.result(index(winner), index(loser)), MatchResult::Win);
+            assert_eq!(game.result(index(loser), index(winner)), MatchResult::Lose);
+        }
+        assert_eq!(game.result(index("Spock"), index("Spock")), MatchResult::Draw);
+    }
+
+    #[test]
+    fn every_move_beats_half_of_the_others() {
+        let game = CyclicGame::from(&["A", "B", "C", "D", "E", "F", "G"]).unwrap();
+        for played in 0..7 {
+            let wins = (0..7).filter(|&opponent| game.result(played, opponent) == MatchResult::Win).count();
+            assert_eq!(wins, 3);
+        }
+    }
+
+    #[test]
+    fn required_move_gives_requested_result() {
+        let game = CyclicGame::rock_paper_scissors_lizard_spock();
+        for opponent in 0..5 {
+            for result in [MatchResult::Win, MatchResult::Draw, MatchResult::Lose] {
+                assert_eq!(game.result(game.required_move(opponent, result), opponent), result);
+            }
+        }
     }
 
     #[test]
     fn convert_line() {
         let line = "A Y";
         let expected = Ok(Game { opponent: Rock, recommended: Paper});
-        assert_eq!(expected, Game::from(line));
+        assert_eq!(expected, PART_ONE.game(line));
     }
 
     #[test]
@@ -280,145 +1214,142 @@ mod tests {
 
     #[test]
     fn a_y_game_score() {
-        let game = Game::from("A Y").unwrap();
-        assert_eq!(game.score(), 8);
+        let game = PART_ONE.game("A Y").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 8);
     }
 
     #[test]
     fn b_x_game_score() {
-        let game = Game::from("B X").unwrap();
-        assert_eq!(game.score(), 1);
+        let game = PART_ONE.game("B X").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 1);
     }
 
     #[test]
     fn c_z_game_score() {
-        let game = Game::from("C Z").unwrap();
-        assert_eq!(game.score(), 6);
+        let game = PART_ONE.game("C Z").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 6);
     }
 
     #[test]
     fn alternate_recommendation_win() {
-        let game = Game::from("A Z").unwrap();
-        assert_eq!(MatchResult::Win, game.alternate_recommendation());
+        assert_eq!(Ok(Response::Aim(MatchResult::Win)), PART_TWO.decode("A Z").map(|(_, response)| response));
     }
 
     #[test]
     fn alternate_recommendation_lose() {
-        let game = Game::from("A X").unwrap();
-        assert_eq!(MatchResult::Lose, game.alternate_recommendation());
+        assert_eq!(Ok(Response::Aim(MatchResult::Lose)), PART_TWO.decode("A X").map(|(_, response)| response));
     }
 
     #[test]
     fn alternate_recommendation_draw() {
-        let game = Game::from("A Y").unwrap();
-        assert_eq!(MatchResult::Draw, game.alternate_recommendation());
+        assert_eq!(Ok(Response::Aim(MatchResult::Draw)), PART_TWO.decode("A Y").map(|(_, response)| response));
     }
 
     #[test]
     fn alternate_rock_win() {
-        let game = Game::from("A Z").unwrap();
-        assert_eq!(game.alternate_result(), Paper);
+        let game = PART_TWO.game("A Z").unwrap();
+        assert_eq!(game.recommended, Paper);
     }
 
     #[test]
     fn alternate_rock_lose() {
-        let game = Game::from("A X").unwrap();
-        assert_eq!(game.alternate_result(), Scissors);
+        let game = PART_TWO.game("A X").unwrap();
+        assert_eq!(game.recommended, Scissors);
     }
 
     #[test]
     fn alternate_rock_draw() {
-        let game = Game::from("A Y").unwrap();
-        assert_eq!(game.alternate_result(), Rock);
+        let game = PART_TWO.game("A Y").unwrap();
+        assert_eq!(game.recommended, Rock);
     }
 
     #[test]
     fn alternate_paper_win() {
-        let game = Game::from("B Z").unwrap();
-        assert_eq!(game.alternate_result(), Scissors);
+        let game = PART_TWO.game("B Z").unwrap();
+        assert_eq!(game.recommended, Scissors);
     }
 
     #[test]
     fn alternate_paper_lose() {
-        let game = Game::from("B X").unwrap();
-        assert_eq!(game.alternate_result(), Rock);
+        let game = PART_TWO.game("B X").unwrap();
+        assert_eq!(game.recommended, Rock);
     }
 
     #[test]
     fn alternate_paper_draw() {
-        let game = Game::from("B Y").unwrap();
-        assert_eq!(game.alternate_result(), Paper);
+        let game = PART_TWO.game("B Y").unwrap();
+        assert_eq!(game.recommended, Paper);
     }
 
     #[test]
     fn alternate_scissors_win() {
-        let game = Game::from("C Z").unwrap();
-        assert_eq!(game.alternate_result(), Rock);
+        let game = PART_TWO.game("C Z").unwrap();
+        assert_eq!(game.recommended, Rock);
     }
 
     #[test]
     fn alternate_scissors_lose() {
-        let game = Game::from("C X").unwrap();
-        assert_eq!(game.alternate_result(), Paper);
+        let game = PART_TWO.game("C X").unwrap();
+        assert_eq!(game.recommended, Paper);
     }
 
     #[test]
     fn alternate_scissors_draw() {
-        let game = Game::from("C Y").unwrap();
-        assert_eq!(game.alternate_result(), Scissors);
+        let game = PART_TWO.game("C Y").unwrap();
+        assert_eq!(game.recommended, Scissors);
     }
 
     #[test]
     fn a_x_alternate_score() {
-        let game = Game::from("A X").unwrap();
-        assert_eq!(game.alternate_score(), 3);
+        let game = PART_TWO.game("A X").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 3);
     }
 
     #[test]
     fn a_y_alternate_score() {
-        let game = Game::from("A Y").unwrap();
-        assert_eq!(game.alternate_score(), 4);
+        let game = PART_TWO.game("A Y").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 4);
     }
 
     #[test]
     fn a_z_alternate_score() {
-        let game = Game::from("A Z").unwrap();
-        assert_eq!(game.alternate_score(), 8);
+        let game = PART_TWO.game("A Z").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 8);
     }
 
     #[test]
     fn b_x_alternate_score() {
-        let game = Game::from("B X").unwrap();
-        assert_eq!(game.alternate_score(), 1);
+        let game = PART_TWO.game("B X").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 1);
     }
 
     #[test]
     fn b_y_alternate_score() {
-        let game = Game::from("B Y").unwrap();
-        assert_eq!(game.alternate_score(), 5);
+        let game = PART_TWO.game("B Y").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 5);
     }
 
     #[test]
     fn b_z_alternate_score() {
-        let game = Game::from("B Z").unwrap();
-        assert_eq!(game.alternate_score(), 9);
+        let game = PART_TWO.game("B Z").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 9);
     }
 
     #[test]
     fn c_x_alternate_score() {
-        let game = Game::from("C X").unwrap();
-        assert_eq!(game.alternate_score(), 2);
+        let game = PART_TWO.game("C X").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 2);
     }
 
     #[test]
     fn c_y_alternate_score() {
-        let game = Game::from("C Y").unwrap();
-        assert_eq!(game.alternate_score(), 6);
+        let game = PART_TWO.game("C Y").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 6);
     }
 
     #[test]
     fn c_z_alternate_score() {
-        let game = Game::from("C Z").unwrap();
-        assert_eq!(game.alternate_score(), 7);
+        let game = PART_TWO.game("C Z").unwrap();
+        assert_eq!(game.score(&ScoringTable::standard()), 7);
     }
 }
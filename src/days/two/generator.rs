@@ -0,0 +1,162 @@
+use advent2022::Random;
+use super::{DecodingSchema, ScoringTable};
+
+const SEARCH_LIMIT: u64 = 1 << 24;
+
+struct FewestRounds { counts: Vec<u32>, largest: u64 }
+
+impl FewestRounds {
+    fn new(steps: &[u32], limit: u64) -> FewestRounds {
+        let largest = steps.iter().copied().max().unwrap_or(0) as u64;
+        let size = (limit + 1).min((largest * largest).max(1));
+        let mut counts = vec![u32::MAX; size as usize];
+        counts[0] = 0;
+        for total in 1..counts.len() {
+            counts[total] = steps
+                .iter()
+                .filter(|step| **step as usize <= total)
+                .map(|step| counts[total - *step as usize])
+                .filter(|count| *count != u32::MAX)
+                .map(|count| count + 1)
+                .min()
+                .unwrap_or(u32::MAX);
+        }
+        FewestRounds { counts, largest }
+    }
+
+    fn within(&self, total: u64, rounds: u64) -> bool {
+        if total < self.counts.len() as u64 {
+            return (self.counts[total as usize] as u64) <= rounds;
+        }
+        if self.largest == 0 {
+            return false;
+        }
+        let size = self.counts.len() as u64;
+        let extra = (total - size) / self.largest + 1;
+        let count = self.counts[(total - extra * self.largest) as usize];
+        count != u32::MAX && count as u64 + extra <= rounds
+    }
+}
+
+pub fn generate(rounds: usize, target: u32, schema: &DecodingSchema, scoring: &ScoringTable, seed: u64) -> Result<Vec<String>, String> {
+    let mut lines: Vec<(String, u32)> = vec![];
+    for (opponent, _) in &schema.opponent {
+        for (response, _) in &schema.response {
+            let line = format!("{} {}", opponent, response);
+            let score = schema.game(&line).map_err(|_| format!("unable to decode {}", line))?.score(scoring);
+            lines.push((line, score));
+        }
+    }
+    let mut scores: Vec<u32> = lines.iter().map(|(_, score)| *score).collect();
+    scores.sort();
+    scores.dedup();
+
+    let unreachable = || format!("a total of {} cannot be reached in {} rounds", target, rounds);
+    let lowest = scores[0] as u64;
+    let highest = scores[scores.len() - 1] as u64;
+    if (target as u64) < lowest * rounds as u64 || (target as u64) > highest * rounds as u64 {
+        return Err(unreachable());
+    }
+
+    let excess = target as u64 - lowest * rounds as u64;
+    let largest = highest - lowest;
+    if excess.min(largest * largest) >= SEARCH_LIMIT {
+        return Err(format!("a total of {} in {} rounds needs too large a search with these scores", target, rounds));
+    }
+    let steps: Vec<u32> = scores.iter().skip(1).map(|score| score - scores[0]).collect();
+    let fewest = FewestRounds::new(&steps, excess);
+    let reachable = |total: u64, rounds: u64| {
+        total >= lowest * rounds && fewest.within(total - lowest * rounds, rounds)
+    };
+    if !reachable(target as u64, rounds as u64) {
+        return Err(unreachable());
+    }
+
+    let mut random = Random::from(seed);
+    let mut remaining = target;
+    let mut guide = Vec::with_capacity(rounds);
+    for round in (0..rounds).rev() {
+        let candidates: Vec<&(String, u32)> = lines
+            .iter()
+            .filter(|(_, score)| *score <= remaining && reachable((remaining - score) as u64, round as u64))
+            .collect();
+        let (line, score) = candidates[random.below(candidates.len())];
+        guide.push(line.clone());
+        remaining -= score;
+    }
+    Ok(guide)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::two::{PART_ONE, PART_TWO};
+
+    #[test]
+    fn generated_guide_hits_target_score() {
+        let guide = generate(100, 537, &PART_ONE, &ScoringTable::standard(), 1).unwrap();
+        assert_eq!(guide.len(), 100);
+        let total: u32 = guide.iter().map(|line| PART_ONE.game(line).unwrap().score(&ScoringTable::standard())).sum();
+        assert_eq!(total, 537);
+    }
+
+    #[test]
+    fn generated_guide_hits_target_alternate_score() {
+        let guide = generate(50, 222, &PART_TWO, &ScoringTable::standard(), 2).unwrap();
+        let total: u32 = guide.iter().map(|line| PART_TWO.game(line).unwrap().score(&ScoringTable::standard())).sum();
+        assert_eq!(total, 222);
+    }
+
+    #[test]
+    fn generated_guide_uses_the_seed() {
+        let first = generate(20, 100, &PART_ONE, &ScoringTable::standard(), 3).unwrap();
+        let second = generate(20, 100, &PART_ONE, &ScoringTable::standard(), 3).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn extremes_are_reachable() {
+        assert_eq!(generate(3, 27, &PART_ONE, &ScoringTable::standard(), 4).unwrap(), vec!["B Z", "B Z", "B Z"]);
+        assert_eq!(generate(3, 3, &PART_ONE, &ScoringTable::standard(), 4).unwrap(), vec!["B X", "B X", "B X"]);
+    }
+
+    #[test]
+    fn target_above_maximum_is_unreachable() {
+        let error = generate(3, 28, &PART_ONE, &ScoringTable::standard(), 5);
+        assert_eq!(error, Err(String::from("a total of 28 cannot be reached in 3 rounds")));
+    }
+
+    #[test]
+    fn target_below_minimum_is_unreachable() {
+        assert!(generate(3, 2, &PART_TWO, &ScoringTable::standard(), 5).is_err());
+    }
+
+    #[test]
+    fn gaps_in_custom_scoring_are_unreachable() {
+        let scoring = ScoringTable::from("Rock = 0\nPaper = 0\nScissors = 0\nWin = 6\nDraw = 3\nLose = 0").unwrap();
+        assert!(generate(2, 7, &PART_ONE, &scoring, 6).is_err());
+        assert!(generate(2, 9, &PART_ONE, &scoring, 6).is_ok());
+    }
+
+    #[test]
+    fn long_guides_stay_small() {
+        let guide = generate(100000, 500000, &PART_ONE, &ScoringTable::standard(), 7).unwrap();
+        assert_eq!(guide.len(), 100000);
+        let total: u32 = guide.iter().map(|line| PART_ONE.game(line).unwrap().score(&ScoringTable::standard())).sum();
+        assert_eq!(total, 500000);
+    }
+
+    #[test]
+    fn gaps_repeat_beyond_the_table() {
+        let scoring = ScoringTable::from("Rock = 0\nPaper = 0\nScissors = 0\nWin = 6\nDraw = 3\nLose = 0").unwrap();
+        assert!(generate(1000, 2999, &PART_ONE, &scoring, 8).is_err());
+        assert!(generate(1000, 3000, &PART_ONE, &scoring, 8).is_ok());
+    }
+
+    #[test]
+    fn huge_score_steps_are_refused() {
+        let scoring = ScoringTable::from("Rock = 1\nPaper = 2\nScissors = 3\nWin = 100000000\nDraw = 3\nLose = 0").unwrap();
+        let error = generate(10, 500000000, &PART_ONE, &scoring, 9);
+        assert_eq!(error, Err(String::from("a total of 500000000 in 10 rounds needs too large a search with these scores")));
+    }
+}
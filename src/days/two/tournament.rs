@@ -0,0 +1,206 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::fmt::Formatter;
+use advent2022::Random;
+use super::{Choice, Game, MatchResult, ScoringTable};
+use super::Choice::Rock;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Strategy {
+    AlwaysRock,
+    Cycle,
+    CopyLast,
+    FrequencyCounter,
+    ReplayGuide(Vec<Choice>),
+}
+
+impl Strategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::AlwaysRock => "always rock",
+            Strategy::Cycle => "cycle",
+            Strategy::CopyLast => "copy last",
+            Strategy::FrequencyCounter => "frequency counter",
+            Strategy::ReplayGuide(_) => "replay the guide",
+        }
+    }
+
+    fn play(&self, round: usize, opponent: &History, random: &mut Random) -> Choice {
+        match self {
+            Strategy::AlwaysRock => Rock,
+            Strategy::Cycle => Choice::from_index(round % 3),
+            Strategy::CopyLast => match opponent.moves.last() {
+                Some(choice) => *choice,
+                None => Choice::from_index(random.below(3)),
+            },
+            Strategy::FrequencyCounter => {
+                let most_frequent = opponent.counts.iter().max().unwrap_or(&0);
+                let candidates: Vec<usize> = (0..3)
+                    .filter(|&index| opponent.counts[index] == *most_frequent)
+                    .collect();
+                let expected = Choice::from_index(candidates[random.below(candidates.len())]);
+                Choice::required_for(expected, MatchResult::Win)
+            }
+            Strategy::ReplayGuide(moves) if !moves.is_empty() => moves[round % moves.len()],
+            Strategy::ReplayGuide(_) => Choice::from_index(random.below(3)),
+        }
+    }
+}
+
+struct History { moves: Vec<Choice>, counts: [usize; 3] }
+
+impl History {
+    fn with_capacity(rounds: usize) -> History {
+        History { moves: Vec::with_capacity(rounds), counts: [0; 3] }
+    }
+
+    fn push(&mut self, choice: Choice) {
+        self.moves.push(choice);
+        self.counts[choice.index()] += 1;
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Standing { name: &'static str, score: u64, wins: u32, draws: u32, losses: u32 }
+
+impl Standing {
+    fn win_rate(&self) -> f64 {
+        let played = self.wins + self.draws + self.losses;
+        if played == 0 {
+            0.0
+        } else {
+            self.wins as f64 / played as f64
+        }
+    }
+
+    fn record(&mut self, game: &Game, scoring: &ScoringTable) {
+        self.score += game.score(scoring) as u64;
+        match game.result() {
+            MatchResult::Win => self.wins += 1,
+            MatchResult::Draw => self.draws += 1,
+            MatchResult::Lose => self.losses += 1,
+        }
+    }
+}
+
+pub struct Standings(Vec<Standing>);
+
+impl fmt::Display for Standings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<20}{:>8}{:>7}{:>7}{:>8}{:>10}", "Strategy", "Score", "Wins", "Draws", "Losses", "Win rate")?;
+        for standing in &self.0 {
+            writeln!(f, "{:<20}{:>8}{:>7}{:>7}{:>8}{:>9.1}%",
+                     standing.name, standing.score, standing.wins, standing.draws, standing.losses,
+                     standing.win_rate() * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn round_robin(strategies: &[Strategy], rounds: usize, seed: u64, scoring: &ScoringTable) -> Standings {
+    let mut random = Random::from(seed);
+    let mut standings: Vec<Standing> = strategies
+        .iter()
+        .map(|strategy| Standing { name: strategy.name(), score: 0, wins: 0, draws: 0, losses: 0 })
+        .collect();
+
+    for first in 0..strategies.len() {
+        for second in first + 1..strategies.len() {
+            let mut first_moves = History::with_capacity(rounds);
+            let mut second_moves = History::with_capacity(rounds);
+            for round in 0..rounds {
+                let first_move = strategies[first].play(round, &second_moves, &mut random);
+                let second_move = strategies[second].play(round, &first_moves, &mut random);
+                first_moves.push(first_move);
+                second_moves.push(second_move);
+                standings[first].record(&Game { opponent: second_move, recommended: first_move }, scoring);
+                standings[second].record(&Game { opponent: first_move, recommended: second_move }, scoring);
+            }
+        }
+    }
+
+    standings.sort_by_key(|standing| Reverse(standing.score));
+    Standings(standings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::two::Choice::{Paper, Scissors};
+
+    fn history(moves: &[Choice]) -> History {
+        let mut history = History::with_capacity(moves.len());
+        for choice in moves {
+            history.push(*choice);
+        }
+        history
+    }
+
+    #[test]
+    fn cycle_plays_every_move_in_turn() {
+        let mut random = Random::from(1);
+        let moves: Vec<Choice> = (0..4).map(|round| Strategy::Cycle.play(round, &history(&[]), &mut random)).collect();
+        assert_eq!(moves, vec![Rock, Paper, Scissors, Rock]);
+    }
+
+    #[test]
+    fn copy_last_repeats_the_opponent() {
+        let mut random = Random::from(1);
+        assert_eq!(Strategy::CopyLast.play(2, &history(&[Rock, Scissors]), &mut random), Scissors);
+    }
+
+    #[test]
+    fn frequency_counter_beats_the_most_common_move() {
+        let mut random = Random::from(1);
+        assert_eq!(Strategy::FrequencyCounter.play(3, &history(&[Rock, Rock, Paper]), &mut random), Paper);
+    }
+
+    #[test]
+    fn replay_the_guide_loops_over_the_guide() {
+        let mut random = Random::from(1);
+        let strategy = Strategy::ReplayGuide(vec![Paper, Scissors]);
+        assert_eq!(strategy.play(3, &history(&[]), &mut random), Scissors);
+    }
+
+    #[test]
+    fn frequency_counter_beats_always_rock() {
+        let strategies = [Strategy::AlwaysRock, Strategy::FrequencyCounter];
+        let Standings(standings) = round_robin(&strategies, 10, 5, &ScoringTable::standard());
+        assert_eq!(standings[0].name, "frequency counter");
+        assert!(standings[0].wins >= 9);
+        assert_eq!(standings[1].wins, 0);
+    }
+
+    #[test]
+    fn same_seed_gives_same_standings() {
+        let strategies = [Strategy::CopyLast, Strategy::FrequencyCounter, Strategy::Cycle];
+        let first = round_robin(&strategies, 50, 9, &ScoringTable::standard());
+        let second = round_robin(&strategies, 50, 9, &ScoringTable::standard());
+        assert_eq!(first.0, second.0);
+    }
+
+    #[test]
+    fn every_round_is_recorded_for_both_players() {
+        let strategies = [Strategy::AlwaysRock, Strategy::Cycle, Strategy::CopyLast];
+        let Standings(standings) = round_robin(&strategies, 7, 3, &ScoringTable::standard());
+        for standing in standings {
+            assert_eq!(standing.wins + standing.draws + standing.losses, 14);
+        }
+    }
+
+    #[test]
+    fn standings_beyond_u32() {
+        let scoring = ScoringTable::from("Rock = 1\nPaper = 2\nScissors = 3\nWin = 4000000000\nDraw = 3\nLose = 0").unwrap();
+        let strategies = [Strategy::AlwaysRock, Strategy::ReplayGuide(vec![Paper])];
+        let Standings(standings) = round_robin(&strategies, 3, 1, &scoring);
+        assert_eq!(standings[0].score, 3 * 4000000002);
+    }
+
+    #[test]
+    fn long_frequency_counter_matches_stay_fast() {
+        let strategies = [Strategy::AlwaysRock, Strategy::FrequencyCounter];
+        let Standings(standings) = round_robin(&strategies, 50000, 5, &ScoringTable::standard());
+        assert_eq!(standings[0].name, "frequency counter");
+        assert!(standings[0].wins >= 49999);
+    }
+}
@@ -1,3 +1,115 @@
+use std::str::FromStr;
+
 pub trait DailyChallenge {
     fn run(&self);
 }
+
+pub struct Arguments {
+    values: Vec<String>,
+}
+
+impl Arguments {
+    pub fn from(values: Vec<String>) -> Arguments {
+        Arguments { values }
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.values.iter().any(|value| value == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .position(|value| value == name)
+            .and_then(|index| self.values.get(index + 1))
+            .map(|value| value.as_str())
+    }
+
+    pub fn number<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.value(name) {
+            Some(value) => value
+                .parse::<T>()
+                .map(Some)
+                .map_err(|_| format!("{} expects a number, found {:?}", name, value)),
+            None if self.flag(name) => Err(format!("{} expects a number", name)),
+            None => Ok(None),
+        }
+    }
+}
+
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn from(seed: u64) -> Random {
+        let state = seed ^ 0x9E37_79B9_7F4A_7C15;
+        Random { state: if state == 0 { 1 } else { state } }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(values: &[&str]) -> Arguments {
+        Arguments::from(values.iter().map(|value| value.to_string()).collect())
+    }
+
+    #[test]
+    fn find_flag() {
+        assert!(arguments(&["--verbose"]).flag("--verbose"));
+        assert!(!arguments(&["--quiet"]).flag("--verbose"));
+    }
+
+    #[test]
+    fn find_value() {
+        let arguments = arguments(&["--input", "guide.txt"]);
+        assert_eq!(arguments.value("--input"), Some("guide.txt"));
+        assert_eq!(arguments.value("--output"), None);
+    }
+
+    #[test]
+    fn parse_number() {
+        assert_eq!(arguments(&["--count", "12"]).number::<u32>("--count"), Ok(Some(12)));
+        assert_eq!(arguments(&["--other", "12"]).number::<u32>("--count"), Ok(None));
+        assert_eq!(arguments(&["--count", "abc"]).number::<u32>("--count"), Err(String::from("--count expects a number, found \"abc\"")));
+        assert_eq!(arguments(&["--count"]).number::<u32>("--count"), Err(String::from("--count expects a number")));
+    }
+
+    #[test]
+    fn missing_value() {
+        assert_eq!(arguments(&["--input"]).value("--input"), None);
+    }
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut first = Random::from(42);
+        let mut second = Random::from(42);
+        for _ in 0..10 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+    }
+
+    #[test]
+    fn different_seeds_give_different_sequences() {
+        assert_ne!(Random::from(1).next_u64(), Random::from(2).next_u64());
+    }
+
+    #[test]
+    fn below_stays_in_bounds() {
+        let mut random = Random::from(7);
+        assert!((0..1000).all(|_| random.below(3) < 3));
+    }
+}
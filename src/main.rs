@@ -1,6 +1,7 @@
 mod days;
 
-use advent2022::DailyChallenge;
+use std::env;
+use advent2022::{Arguments, DailyChallenge};
 use days::one::One;
 use days::two::Two;
 use days::three::Three;
@@ -9,11 +10,12 @@ use days::five::Five;
 
 fn main() {
     println!("Advent of code 2022");
+    let arguments = Arguments::from(env::args().skip(1).collect());
     let days: Vec<Box<dyn DailyChallenge>> = vec![
         Box::new(One {}),
-        Box::new(Two {}),
-        Box::new(Three {}),
-        Box::new(Four {}),
+        Box::new(Two::from(&arguments)),
+        Box::new(Three::from(&arguments)),
+        Box::new(Four::from(&arguments)),
         Box::new(Five {}),
     ];
     for day in days {